@@ -1,9 +1,11 @@
 use std::path::PathBuf;
-use {WindowId, DeviceId};
+use {WindowId, DeviceId, MonitorId, TimerId};
 
 /// Describes a generic event.
+///
+/// `T` is the type of the events sent with `EventsLoopProxy::send_event`.
 #[derive(Clone, Debug)]
-pub enum Event {
+pub enum Event<T = ()> {
     WindowEvent {
         window_id: WindowId,
         event: WindowEvent,
@@ -14,10 +16,40 @@ pub enum Event {
     },
     Awakened,
 
+    /// Emitted before the events of a new batch, when the events loop starts processing the
+    /// events that are available or wakes up.
+    ///
+    /// Only emitted on X11 and Wayland for now.
+    NewEvents,
+
+    /// Emitted when all the events of a batch have been processed, and the events loop is about
+    /// to return or to wait for more events. This is the right time to redraw once in response to
+    /// a series of events.
+    ///
+    /// Only emitted on X11 and Wayland for now.
+    EventsCleared,
+
     /// The application has been suspended or resumed.
     ///
     /// The parameter is true if app was suspended, and false if it has been resumed.
     Suspended(bool),
+
+    /// A timer registered with `EventsLoop::add_timer` expired.
+    Timer(TimerId),
+
+    /// A monitor was connected.
+    ///
+    /// Only emitted on X11 and Wayland for now, like the other monitor events.
+    MonitorAdded(MonitorId),
+
+    /// A monitor was disconnected. The `MonitorId` describes the monitor as it was before.
+    MonitorRemoved(MonitorId),
+
+    /// The configuration of a monitor changed, for example its video mode, position or rotation.
+    MonitorChanged(MonitorId),
+
+    /// An event sent with `EventsLoopProxy::send_event`.
+    User(T),
 }
 
 /// Describes an event from a `Window`.
@@ -86,11 +118,30 @@ pub enum WindowEvent {
     AxisMotion { device_id: DeviceId, axis: AxisId, value: f64 },
 
     /// The window needs to be redrawn.
+    ///
+    /// Deprecated in favor of `RedrawRequested`, and no longer emitted on X11 and Wayland.
     Refresh,
 
+    /// The window should be redrawn, either because `Window::request_redraw` was called or
+    /// because the system asked for it.
+    ///
+    /// On X11 and Wayland, this is emitted at most once per window and per batch of events,
+    /// right before `Event::EventsCleared`, and replaces `Refresh`.
+    RedrawRequested,
+
+    /// The compositor signaled that it is a good time to draw a new frame.
+    ///
+    /// Only emitted on Wayland, once frame callbacks have been enabled with
+    /// `os::unix::WindowExt::set_wayland_frame_callbacks`. No frame is signaled while the window
+    /// is hidden.
+    FrameReady,
+
     /// Touch event has been received
     Touch(Touch),
 
+    /// A pen, or another tool of a graphics tablet, was used over the window.
+    Pen(Pen),
+
     /// DPI scaling factor of the window has changed.
     ///
     /// The following actions cause DPI changes:
@@ -188,13 +239,77 @@ pub enum TouchPhase {
 /// as previously received End event is a new finger and has nothing to do with an old one.
 ///
 /// Touch may be cancelled if for example window lost focus.
+///
+/// The force and the geometry of the contact are `None` if the device doesn't report them. They
+/// are not reported on Wayland and Android.
 #[derive(Debug, Clone, Copy)]
 pub struct Touch {
     pub device_id: DeviceId,
     pub phase: TouchPhase,
     pub location: (f64,f64),
     /// unique identifier of a finger.
-    pub id: u64
+    pub id: u64,
+    /// How hard the finger presses, from 0.0 to 1.0.
+    pub force: Option<f64>,
+    /// The major and minor radius, in pixels, of the ellipse that approximates the contact area.
+    /// Only touchscreens report it, the contacts on a touchpad have no size on the screen.
+    pub radius: Option<(f64, f64)>,
+    /// The angle in degrees between the major axis of the contact area and the y axis, clockwise.
+    pub orientation: Option<f64>,
+}
+
+/// Describes the phase of a `Pen` event.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum PenPhase {
+    /// The tool came over the window, in proximity of the tablet.
+    Entered,
+    /// The tool moved, or its state changed.
+    Moved,
+    /// The tool left the window, or the proximity of the tablet.
+    Left,
+}
+
+/// Describes the tool of a graphics tablet.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum PenTool {
+    Pen,
+    Eraser,
+    Brush,
+    Pencil,
+    Airbrush,
+    Finger,
+    /// A puck, used like a mouse on the tablet.
+    Mouse,
+    Lens,
+}
+
+/// Represents the state of a pen, or of another tool of a graphics tablet, over a window.
+///
+/// A `Pen` event is emitted each time the tool moves or its state changes. The axes that the tool
+/// doesn't have are `None`.
+///
+/// ## Platform-specific
+///
+/// - On X11, the tilt is the raw value of the valuator, which is in degrees with most drivers.
+///   The rotation is only known with the libinput driver.
+#[derive(Debug, Clone, Copy)]
+pub struct Pen {
+    pub device_id: DeviceId,
+    pub phase: PenPhase,
+    pub tool: PenTool,
+    pub location: (f64, f64),
+    /// Whether the tip of the tool touches the tablet. The tool hovers over it otherwise.
+    pub down: bool,
+    /// The pressed buttons on the barrel of the tool, as a bit mask where bit 0 is the first button.
+    pub barrel_buttons: u32,
+    /// How hard the tip is pressed, from 0.0 to 1.0.
+    pub pressure: Option<f64>,
+    /// The tilt of the tool along the x and y axes, in degrees from the perpendicular to the tablet.
+    pub tilt: Option<(f64, f64)>,
+    /// The rotation of the tool around its axis, in degrees clockwise from its natural position.
+    pub rotation: Option<f64>,
+    /// The distance of the tool from the tablet, from 0.0 to 1.0.
+    pub distance: Option<f64>,
 }
 
 /// Hardware-dependent keyboard scan code.
@@ -112,10 +112,21 @@ extern crate x11_dl;
 extern crate wayland_client;
 
 pub use events::*;
-pub use window::{AvailableMonitorsIter, MonitorId};
+pub use window::{AvailableMonitorsIter, Fullscreen, MonitorId, MonitorRotation, SubpixelLayout, VideoMode,
+                 VideoModesIter};
+
+use std::cell::{Cell, RefCell};
+use std::collections::vec_deque::IntoIter as VecDequeIter;
+use std::rc::Rc;
+use std::sync::{mpsc, Arc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use timers::Timers;
 
 mod platform;
 mod events;
+mod timers;
 mod window;
 
 pub mod os;
@@ -160,6 +171,90 @@ pub struct WindowId(platform::WindowId);
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DeviceId(platform::DeviceId);
 
+/// An iterator for the list of available input devices.
+// Implementation note: we retrieve the list once, then serve each element by one by one.
+pub struct AvailableDevicesIter {
+    data: VecDequeIter<platform::DeviceId>,
+}
+
+impl Iterator for AvailableDevicesIter {
+    type Item = DeviceId;
+
+    #[inline]
+    fn next(&mut self) -> Option<DeviceId> {
+        self.data.next().map(DeviceId)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.data.size_hint()
+    }
+}
+
+/// Information about an input device, returned by `EventsLoop::get_device_info`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    /// The name of the device, as reported by the system.
+    pub name: String,
+    pub kind: DeviceKind,
+    /// The axes whose motion is reported with `DeviceEvent::Motion` and `WindowEvent::AxisMotion`.
+    pub axes: Vec<AxisInfo>,
+    /// The number of buttons of the device, `0` if it has none or if it is unknown.
+    pub buttons: u32,
+}
+
+/// The kind of an input device.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceKind {
+    Mouse,
+    Keyboard,
+    Touchscreen,
+    Touchpad,
+    /// A graphics tablet, or a pen.
+    Tablet,
+    /// A device of another kind, or whose kind couldn't be determined.
+    Other,
+}
+
+/// An axis of an input device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisInfo {
+    pub id: AxisId,
+    /// The name of the axis given by the system, for example `"Abs Pressure"` on X11.
+    pub label: Option<String>,
+    /// The minimum value of the axis. It is equal to `max` if the axis has no range.
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Identifier of a timer registered with `EventsLoop::add_timer`.
+///
+/// It is contained in the `Event::Timer` emitted each time the timer expires.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerId(u64);
+
+/// A handle to the timers of an `EventsLoop`, returned by `EventsLoop::timer_handle`.
+///
+/// Contrary to the `EventsLoop`, it can be used from within the callback given to `run_forever`,
+/// for example to start a key repeat timer when a key is pressed. Like the `EventsLoop`, it can't
+/// be sent to another thread.
+#[derive(Clone)]
+pub struct TimerHandle {
+    timers: Rc<RefCell<Timers>>,
+}
+
+impl TimerHandle {
+    /// Registers a timer. See `EventsLoop::add_timer`.
+    pub fn add_timer(&self, duration: Duration, repeating: bool) -> TimerId {
+        self.timers.borrow_mut().add(Instant::now(), duration, repeating)
+    }
+
+    /// Unregisters a timer. See `EventsLoop::cancel_timer`.
+    pub fn cancel_timer(&self, id: TimerId) -> bool {
+        self.timers.borrow_mut().cancel(id)
+    }
+}
+
 /// Provides a way to retreive events from the system and from the windows that were registered to
 /// the events loop.
 ///
@@ -173,8 +268,17 @@ pub struct DeviceId(platform::DeviceId);
 /// forbiding it), as such it is neither `Send` nor `Sync`. If you need cross-thread access, the
 /// `Window` created from this `EventsLoop` _can_ be sent to an other thread, and the
 /// `EventsLoopProxy` allows you to wakeup an `EventsLoop` from an other thread.
-pub struct EventsLoop {
+pub struct EventsLoop<T = ()> {
     events_loop: platform::EventsLoop,
+    // Queue of the events sent with `EventsLoopProxy::send_event`. It is kept here rather than in
+    // the platforms, which only know about `Event<()>`, so that the user events are delivered the
+    // same way on all of them. The platforms only wake the loop up.
+    user_events: mpsc::Receiver<T>,
+    user_events_sender: mpsc::Sender<T>,
+    // Whether `EventsLoopProxy::wakeup` was called, as opposed to the loop being woken up only
+    // to deliver user events.
+    pending_wakeup: Arc<AtomicBool>,
+    timers: Rc<RefCell<Timers>>,
     _marker: ::std::marker::PhantomData<*mut ()> // Not Send nor Sync
 }
 
@@ -195,10 +299,54 @@ impl EventsLoop {
     /// Usage will result in display backend initialisation, this can be controlled on linux
     /// using an environment variable `WINIT_UNIX_BACKEND`. Legal values are `x11` and `wayland`.
     /// If it is not set, winit will try to connect to a wayland connection, and if it fails will
-    /// fallback on x11. If this variable is set with any other value, winit will panic.
+    /// fallback on x11.
+    ///
+    /// # Panic
+    ///
+    /// Panics if no backend could be initialized. See `try_new` for a non-panicking version.
     pub fn new() -> EventsLoop {
+        EventsLoop::with_user_events()
+    }
+
+    /// Builds a new events loop, returning an error if no display backend could be initialized.
+    ///
+    /// See the documentation of `new` for how the backend is chosen on linux. If the
+    /// `WINIT_UNIX_BACKEND` environment variable is set with an unknown value, an error is
+    /// returned.
+    pub fn try_new() -> Result<EventsLoop, EventsLoopCreationError> {
+        EventsLoop::try_with_user_events()
+    }
+}
+
+impl<T> EventsLoop<T> {
+    /// Builds a new events loop that can receive events of type `T` sent with
+    /// `EventsLoopProxy::send_event`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if no backend could be initialized. See `try_with_user_events` for a
+    /// non-panicking version.
+    pub fn with_user_events() -> EventsLoop<T> {
+        match EventsLoop::try_with_user_events() {
+            Ok(events_loop) => events_loop,
+            Err(err) => panic!("Failed to initialize the events loop: {}", err),
+        }
+    }
+
+    /// Builds a new events loop that can receive events of type `T`, returning an error if no
+    /// display backend could be initialized.
+    pub fn try_with_user_events() -> Result<EventsLoop<T>, EventsLoopCreationError> {
+        Ok(EventsLoop::from_platform(try!(platform::EventsLoop::new())))
+    }
+
+    fn from_platform(events_loop: platform::EventsLoop) -> EventsLoop<T> {
+        let (sender, receiver) = mpsc::channel();
         EventsLoop {
-            events_loop: platform::EventsLoop::new(),
+            events_loop: events_loop,
+            user_events: receiver,
+            user_events_sender: sender,
+            pending_wakeup: Arc::new(AtomicBool::new(false)),
+            timers: Rc::new(RefCell::new(Timers::new())),
             _marker: ::std::marker::PhantomData,
         }
     }
@@ -218,47 +366,278 @@ impl EventsLoop {
         MonitorId { inner: self.events_loop.get_primary_monitor() }
     }
 
+    /// Returns the list of all the input devices connected to the system.
+    ///
+    /// The devices connected later are announced with `DeviceEvent::Added`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - On Wayland, the devices are the pointer, keyboard and touch capabilities of the seats, and
+    ///   the tablets of each seat as a whole.
+    /// - The list is always empty on the platforms other than X11 and Wayland.
+    ///
+    // Note: should be replaced with `-> impl Iterator` once stable.
+    #[inline]
+    pub fn get_available_devices(&self) -> AvailableDevicesIter {
+        let data = self.events_loop.get_available_devices();
+        AvailableDevicesIter { data: data.into_iter() }
+    }
+
+    /// Returns information about an input device, or `None` if the device is unknown or has been
+    /// removed.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - On Wayland, the devices are the pointer, keyboard and touch capabilities of the seats, and
+    ///   the tablets of each seat as a whole.
+    ///   Their axes and buttons are not known.
+    /// - `None` is always returned on the platforms other than X11 and Wayland.
+    #[inline]
+    pub fn get_device_info(&self, device: DeviceId) -> Option<DeviceInfo> {
+        self.events_loop.get_device_info(&device.0)
+    }
+
     /// Fetches all the events that are pending, calls the callback function for each of them,
     /// and returns.
     #[inline]
-    pub fn poll_events<F>(&mut self, callback: F)
-        where F: FnMut(Event)
+    pub fn poll_events<F>(&mut self, mut callback: F)
+        where F: FnMut(Event<T>)
+    {
+        self.dispatch_events(None, &mut callback);
+    }
+
+    /// Fetches all the events that are pending and calls the callback function for each of them.
+    /// If no event is available, sleeps the current thread until an event arrives or `timeout`
+    /// has elapsed, then dispatches whatever was received and returns.
+    ///
+    /// This is useful for frame-paced applications that need to wake up regularly without
+    /// spinning on `poll_events`.
+    #[inline]
+    pub fn wait_events_timeout<F>(&mut self, timeout: std::time::Duration, mut callback: F)
+        where F: FnMut(Event<T>)
     {
-        self.events_loop.poll_events(callback)
+        // Stop waiting when the next timer expires.
+        let timeout = self.timers.borrow().timeout(Instant::now(), Some(timeout)).unwrap_or(timeout);
+        self.dispatch_events(Some(timeout), &mut callback);
     }
 
     /// Calls `callback` every time an event is received. If no event is available, sleeps the
     /// current thread and waits for an event. If the callback returns `ControlFlow::Break` then
     /// `run_forever` will immediately return.
     #[inline]
-    pub fn run_forever<F>(&mut self, callback: F)
-        where F: FnMut(Event) -> ControlFlow
+    pub fn run_forever<F>(&mut self, mut callback: F)
+        where F: FnMut(Event<T>) -> ControlFlow
     {
-        self.events_loop.run_forever(callback)
+        // Track whether or not `Break` was returned. All the events that are dispatched at once,
+        // like several user events, are still delivered.
+        let control_flow = Cell::new(ControlFlow::Continue);
+        let mut callback = |event| if let ControlFlow::Break = callback(event) {
+            control_flow.set(ControlFlow::Break);
+        };
+
+        while control_flow.get() == ControlFlow::Continue {
+            // The callback can add and cancel timers, so the deadline is computed again for each
+            // wait. Emscripten can't wait without blocking the browser, so timers don't fire in
+            // `run_forever` there.
+            let timeout = if cfg!(target_os = "emscripten") {
+                None
+            } else {
+                self.timers.borrow().timeout(Instant::now(), None)
+            };
+            if let Some(timeout) = timeout {
+                self.dispatch_events(Some(timeout), &mut callback);
+                continue;
+            }
+
+            // Without timers, the platform waits for events by itself until the callback breaks
+            // or adds a timer.
+            let user_events = &self.user_events;
+            let pending_wakeup = &self.pending_wakeup;
+            let timers = &self.timers;
+            self.events_loop.run_forever(|event| {
+                dispatch_platform_event(event, user_events, pending_wakeup, timers, &mut callback);
+                if !cfg!(target_os = "emscripten") && timers.borrow().next_deadline().is_some() {
+                    return ControlFlow::Break;
+                }
+                control_flow.get()
+            })
+        }
+    }
+
+    // Dispatches the events of the platform, waiting for them for at most `timeout` if there is
+    // one, then the user events and the timers that are left.
+    fn dispatch_events<F>(&mut self, timeout: Option<Duration>, callback: &mut F)
+        where F: FnMut(Event<T>)
+    {
+        let user_events = &self.user_events;
+        let pending_wakeup = &self.pending_wakeup;
+        let timers = &self.timers;
+        let mut batch_cleared = false;
+        {
+            let dispatch = |event| {
+                batch_cleared |= is_events_cleared(&event);
+                dispatch_platform_event(event, user_events, pending_wakeup, timers, callback)
+            };
+            match timeout {
+                Some(timeout) => self.events_loop.wait_events_timeout(timeout, dispatch),
+                None => self.events_loop.poll_events(dispatch),
+            }
+        }
+        // The platforms that don't emit `EventsCleared` don't necessarily report their wakeups
+        // either, so deliver the events that are left as well.
+        if !batch_cleared {
+            dispatch_user_events(user_events, callback);
+            fire_timers(timers, callback);
+        }
+    }
+
+    /// Registers a timer that emits an `Event::Timer` once `duration` has elapsed, and then every
+    /// `duration` if `repeating` is true.
+    ///
+    /// The events are emitted from within `run_forever`, `poll_events` and
+    /// `wait_events_timeout`, which stop waiting for events when a timer expires. A repeating
+    /// timer that expired several times since the last call only emits one event. The interval of
+    /// a repeating timer is at least one millisecond, a shorter `duration` is rounded up.
+    ///
+    /// To add timers from within the callback given to `run_forever`, use a `TimerHandle`.
+    ///
+    /// On emscripten, the events loop can't wait for the next deadline, so the timers are only
+    /// fired by `poll_events` and `wait_events_timeout`, not by `run_forever`.
+    pub fn add_timer(&self, duration: Duration, repeating: bool) -> TimerId {
+        self.timers.borrow_mut().add(Instant::now(), duration, repeating)
+    }
+
+    /// Unregisters a timer. Returns `false` if the timer doesn't exist anymore, which is the case
+    /// of a non-repeating timer that already expired.
+    pub fn cancel_timer(&self, id: TimerId) -> bool {
+        self.timers.borrow_mut().cancel(id)
+    }
+
+    /// Returns a handle that can add and cancel the timers of this `EventsLoop` from within the
+    /// callback given to `run_forever`.
+    pub fn timer_handle(&self) -> TimerHandle {
+        TimerHandle { timers: self.timers.clone() }
     }
 
     /// Creates an `EventsLoopProxy` that can be used to wake up the `EventsLoop` from another
     /// thread.
-    pub fn create_proxy(&self) -> EventsLoopProxy {
+    pub fn create_proxy(&self) -> EventsLoopProxy<T> {
         EventsLoopProxy {
             events_loop_proxy: self.events_loop.create_proxy(),
+            user_events_sender: self.user_events_sender.clone(),
+            pending_wakeup: self.pending_wakeup.clone(),
         }
     }
 }
 
-/// Used to wake up the `EventsLoop` from another thread.
-#[derive(Clone)]
-pub struct EventsLoopProxy {
+// Forwards an event of the platform to the user callback.
+//
+// The `Awakened` events of the platform are replaced with the user events that were sent since
+// the last wakeup, followed by `Awakened` if `EventsLoopProxy::wakeup` was called.
+fn dispatch_platform_event<T, F>(event: Event, user_events: &mpsc::Receiver<T>,
+                                 pending_wakeup: &AtomicBool, timers: &RefCell<Timers>, callback: &mut F)
+    where F: FnMut(Event<T>)
+{
+    let event = match event {
+        Event::WindowEvent { window_id, event } => Event::WindowEvent {
+            window_id: window_id,
+            event: event,
+        },
+        Event::DeviceEvent { device_id, event } => Event::DeviceEvent {
+            device_id: device_id,
+            event: event,
+        },
+        Event::Suspended(suspended) => Event::Suspended(suspended),
+        Event::Timer(id) => Event::Timer(id),
+        Event::MonitorAdded(monitor) => Event::MonitorAdded(monitor),
+        Event::MonitorRemoved(monitor) => Event::MonitorRemoved(monitor),
+        Event::MonitorChanged(monitor) => Event::MonitorChanged(monitor),
+        Event::NewEvents => Event::NewEvents,
+        Event::EventsCleared => {
+            // The user events and timers are part of the batch.
+            dispatch_user_events(user_events, callback);
+            fire_timers(timers, callback);
+            Event::EventsCleared
+        },
+        Event::Awakened => {
+            dispatch_user_events(user_events, callback);
+            if !pending_wakeup.swap(false, Ordering::Relaxed) {
+                return;
+            }
+            Event::Awakened
+        },
+        // The platforms don't know about the user event type.
+        Event::User(()) => return,
+    };
+    callback(event)
+}
+
+// The platforms that emit `EventsCleared` deliver the user events and fire the timers at the end of
+// each batch, in `dispatch_platform_event`. The others get them after the events are dispatched.
+fn is_events_cleared(event: &Event) -> bool {
+    match *event {
+        Event::EventsCleared => true,
+        _ => false,
+    }
+}
+
+// Emits an `Event::Timer` for each timer that expired. The callback can add or cancel timers, so
+// the timers aren't borrowed while it is called.
+fn fire_timers<T, F>(timers: &RefCell<Timers>, callback: &mut F)
+    where F: FnMut(Event<T>)
+{
+    let expired = timers.borrow_mut().expire(Instant::now());
+    for id in expired {
+        callback(Event::Timer(id));
+    }
+}
+
+// Calls the user callback with all the events that were sent through `EventsLoopProxy::send_event`
+// and haven't been delivered yet.
+fn dispatch_user_events<T, F>(user_events: &mpsc::Receiver<T>, callback: &mut F)
+    where F: FnMut(Event<T>)
+{
+    while let Ok(user_event) = user_events.try_recv() {
+        callback(Event::User(user_event));
+    }
+}
+
+/// Used to wake up the `EventsLoop` from another thread, and to send it user events of type `T`.
+pub struct EventsLoopProxy<T = ()> {
     events_loop_proxy: platform::EventsLoopProxy,
+    user_events_sender: mpsc::Sender<T>,
+    pending_wakeup: Arc<AtomicBool>,
 }
 
-impl EventsLoopProxy {
+impl<T> Clone for EventsLoopProxy<T> {
+    fn clone(&self) -> EventsLoopProxy<T> {
+        EventsLoopProxy {
+            events_loop_proxy: self.events_loop_proxy.clone(),
+            user_events_sender: self.user_events_sender.clone(),
+            pending_wakeup: self.pending_wakeup.clone(),
+        }
+    }
+}
+
+impl<T> EventsLoopProxy<T> {
     /// Wake up the `EventsLoop` from which this proxy was created.
     ///
     /// This causes the `EventsLoop` to emit an `Awakened` event.
     ///
     /// Returns an `Err` if the associated `EventsLoop` no longer exists.
     pub fn wakeup(&self) -> Result<(), EventsLoopClosed> {
+        self.pending_wakeup.store(true, Ordering::Relaxed);
+        self.events_loop_proxy.wakeup()
+    }
+
+    /// Sends an event to the `EventsLoop` from which this proxy was created, and wakes it up.
+    ///
+    /// The `EventsLoop` emits an `Event::User` for each event sent, in the order they were sent.
+    /// Contrary to `wakeup`, several calls to this method are never coalesced.
+    ///
+    /// Returns an `Err` if the associated `EventsLoop` no longer exists.
+    pub fn send_event(&self, event: T) -> Result<(), EventsLoopClosed> {
+        try!(self.user_events_sender.send(event).map_err(|_| EventsLoopClosed));
         self.events_loop_proxy.wakeup()
     }
 }
@@ -280,6 +659,74 @@ impl std::error::Error for EventsLoopClosed {
     }
 }
 
+/// Error that can happen while creating an `EventsLoop`.
+#[derive(Debug, Clone)]
+pub enum EventsLoopCreationError {
+    /// The `WINIT_UNIX_BACKEND` environment variable contains an unknown value.
+    UnknownBackend(String),
+    /// Connecting to the wayland compositor failed.
+    WaylandConnectionFailed(String),
+    /// Loading xlib or connecting to the X server failed.
+    #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+    XNotSupported(platform::XNotSupported),
+    /// The display server doesn't support an extension that winit requires.
+    MissingExtension(&'static str),
+    /// The platform returned an error while initializing the events loop.
+    OsError(String),
+    /// None of the backends could be initialized. Contains the error returned by each backend
+    /// that was tried, in order.
+    NoBackendAvailable(Vec<EventsLoopCreationError>),
+}
+
+impl std::fmt::Display for EventsLoopCreationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            EventsLoopCreationError::UnknownBackend(ref value) => {
+                write!(f, "Unknown value `{}` for the `WINIT_UNIX_BACKEND` environment variable, \
+                           try one of `x11`, `wayland`", value)
+            },
+            EventsLoopCreationError::WaylandConnectionFailed(ref text) => {
+                write!(f, "Failed to connect to the wayland compositor: {}", text)
+            },
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            EventsLoopCreationError::XNotSupported(ref err) => write!(f, "{}", err),
+            EventsLoopCreationError::MissingExtension(name) => {
+                write!(f, "The display server is missing the {} extension", name)
+            },
+            EventsLoopCreationError::OsError(ref text) => f.write_str(text),
+            EventsLoopCreationError::NoBackendAvailable(ref errors) => {
+                try!(f.write_str("No backend is available"));
+                for err in errors {
+                    try!(write!(f, "; {}", err));
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl std::error::Error for EventsLoopCreationError {
+    fn description(&self) -> &str {
+        match *self {
+            EventsLoopCreationError::UnknownBackend(_) => "Unknown value for `WINIT_UNIX_BACKEND`",
+            EventsLoopCreationError::WaylandConnectionFailed(_) => "Failed to connect to the wayland compositor",
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            EventsLoopCreationError::XNotSupported(ref err) => std::error::Error::description(err),
+            EventsLoopCreationError::MissingExtension(_) => "The display server is missing a required extension",
+            EventsLoopCreationError::OsError(ref text) => text,
+            EventsLoopCreationError::NoBackendAvailable(_) => "No backend is available",
+        }
+    }
+
+    fn cause(&self) -> Option<&std::error::Error> {
+        match *self {
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            EventsLoopCreationError::XNotSupported(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
 /// Object that allows you to build windows.
 #[derive(Clone)]
 pub struct WindowBuilder {
@@ -296,26 +743,54 @@ pub enum CreationError {
     OsError(String),
     /// TODO: remove this error
     NotSupported,
-}
-
-impl CreationError {
-    fn to_string(&self) -> &str {
-        match *self {
-            CreationError::OsError(ref text) => &text,
-            CreationError::NotSupported => "Some of the requested attributes are not supported",
-        }
-    }
+    /// The platform can't honor the field `name` of the `WindowAttributes`.
+    UnsupportedAttribute {
+        name: &'static str,
+    },
+    /// A request to the X server failed.
+    #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+    X11(platform::XError),
+    /// The wayland compositor reported an error.
+    #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+    Wayland(platform::WaylandError),
 }
 
 impl std::fmt::Display for CreationError {
     fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        formatter.write_str(self.to_string())
+        match *self {
+            CreationError::UnsupportedAttribute { name } => {
+                write!(formatter, "The `{}` window attribute is not supported", name)
+            },
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            CreationError::X11(ref err) => std::fmt::Display::fmt(err, formatter),
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            CreationError::Wayland(ref err) => std::fmt::Display::fmt(err, formatter),
+            _ => formatter.write_str(std::error::Error::description(self)),
+        }
     }
 }
 
 impl std::error::Error for CreationError {
     fn description(&self) -> &str {
-        self.to_string()
+        match *self {
+            CreationError::OsError(ref text) => &text,
+            CreationError::NotSupported => "Some of the requested attributes are not supported",
+            CreationError::UnsupportedAttribute { .. } => "One of the requested attributes is not supported",
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            CreationError::X11(ref err) => std::error::Error::description(err),
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            CreationError::Wayland(ref err) => std::error::Error::description(err),
+        }
+    }
+
+    fn cause(&self) -> Option<&std::error::Error> {
+        match *self {
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            CreationError::X11(ref err) => Some(err),
+            #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
+            CreationError::Wayland(ref err) => Some(err),
+            _ => None,
+        }
     }
 }
 
@@ -390,7 +865,24 @@ pub enum CursorState {
     /// the cursor.
     ///
     /// This is useful for first-person cameras for example.
+    ///
+    /// The exact behavior depends on the platform: prefer `Confine` or `Lock`, which behave the
+    /// same way everywhere they are supported.
     Grab,
+
+    /// Confines the cursor to the window. The cursor stays visible and keeps moving, but can't
+    /// leave the window.
+    ///
+    /// This is useful for editors, where dragging should not leave the window for example.
+    ///
+    /// Not supported on macOS and emscripten.
+    Confine,
+
+    /// Hides the cursor and locks it in place. Its motion is only reported by
+    /// `DeviceEvent::MouseMotion`, and `WindowEvent::CursorMoved` is not emitted.
+    ///
+    /// This is useful for first-person cameras for example.
+    Lock,
 }
 
 /// Attributes to use when creating a window.
@@ -415,7 +907,7 @@ pub struct WindowAttributes {
     /// Whether the window should be set as fullscreen upon creation.
     ///
     /// The default is `None`.
-    pub fullscreen: Option<MonitorId>,
+    pub fullscreen: Option<Fullscreen>,
 
     /// The title of the window in the title bar.
     ///
@@ -1,9 +1,12 @@
 #![cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
 
+use std::os::unix::io::RawFd;
 use std::sync::Arc;
 use std::ptr;
 use libc;
+use Event;
 use EventsLoop;
+use EventsLoopCreationError;
 use MonitorId;
 use Window;
 use platform::EventsLoop as LinuxEventsLoop;
@@ -16,16 +19,19 @@ use platform::x11::ffi::XVisualInfo;
 #[doc(hidden)]
 pub use platform::x11;
 
-pub use platform::XNotSupported;
+pub use platform::{WaylandError, XError, XNotSupported};
 
 /// Additional methods on `EventsLoop` that are specific to Linux.
 pub trait EventsLoopExt {
+    /// The type of the events sent with `EventsLoopProxy::send_event`.
+    type UserEvent;
+
     /// Builds a new `EventsLoop` that is forced to use X11.
-    fn new_x11() -> Result<Self, XNotSupported>
+    fn new_x11() -> Result<Self, EventsLoopCreationError>
         where Self: Sized;
 
     /// Builds a new `EventsLoop` that is forced to use Wayland.
-    fn new_wayland() -> Self
+    fn new_wayland() -> Result<Self, EventsLoopCreationError>
         where Self: Sized;
 
     /// True if the `EventsLoop` uses Wayland.
@@ -34,30 +40,36 @@ pub trait EventsLoopExt {
     /// True if the `EventsLoop` uses X11.
     fn is_x11(&self) -> bool;
 
+    /// Returns the file descriptor of the connection to the display server, which is the
+    /// `ConnectionNumber` of the X11 display or the fd of the `wl_display`.
+    ///
+    /// This allows the events loop to be driven by an external poller: wait for the fd to become
+    /// readable, then call `dispatch_pending`.
+    fn get_fd(&self) -> RawFd;
+
+    /// Processes the events that are available without blocking and calls `callback` for each of
+    /// them.
+    ///
+    /// Contrary to `poll_events`, the requests made by the callback are flushed to the display
+    /// server before returning, so that it is safe to wait on the fd returned by `get_fd`.
+    fn dispatch_pending<F>(&mut self, callback: F)
+        where F: FnMut(Event<Self::UserEvent>);
+
     #[doc(hidden)]
     fn get_xlib_xconnection(&self) -> Option<Arc<XConnection>>;
 }
 
-impl EventsLoopExt for EventsLoop {
+impl<T> EventsLoopExt for EventsLoop<T> {
+    type UserEvent = T;
+
     #[inline]
-    fn new_x11() -> Result<Self, XNotSupported> {
-        LinuxEventsLoop::new_x11().map(|ev|
-            EventsLoop {
-                events_loop: ev,
-                _marker: ::std::marker::PhantomData,
-            }
-        )
+    fn new_x11() -> Result<Self, EventsLoopCreationError> {
+        LinuxEventsLoop::new_x11().map(EventsLoop::from_platform)
     }
 
     #[inline]
-    fn new_wayland() -> Self {
-        EventsLoop {
-            events_loop: match LinuxEventsLoop::new_wayland() {
-                Ok(e) => e,
-                Err(_) => panic!()      // TODO: propagate
-            },
-            _marker: ::std::marker::PhantomData,
-        }
+    fn new_wayland() -> Result<Self, EventsLoopCreationError> {
+        LinuxEventsLoop::new_wayland().map(EventsLoop::from_platform)
     }
 
     #[inline]
@@ -70,6 +82,24 @@ impl EventsLoopExt for EventsLoop {
         !self.events_loop.is_wayland()
     }
 
+    #[inline]
+    fn get_fd(&self) -> RawFd {
+        self.events_loop.get_fd()
+    }
+
+    #[inline]
+    fn dispatch_pending<F>(&mut self, mut callback: F)
+        where F: FnMut(Event<T>)
+    {
+        let user_events = &self.user_events;
+        let pending_wakeup = &self.pending_wakeup;
+        let timers = &self.timers;
+        self.events_loop.dispatch_pending(|event| {
+            ::dispatch_platform_event(event, user_events, pending_wakeup, timers, &mut callback)
+        });
+        ::fire_timers(timers, &mut callback);
+    }
+
     #[inline]
     fn get_xlib_xconnection(&self) -> Option<Arc<XConnection>> {
         self.events_loop.x_connection().cloned()
@@ -119,6 +149,21 @@ pub trait WindowExt {
     /// The pointer will become invalid when the glutin `Window` is destroyed.
     fn get_wayland_display(&self) -> Option<*mut libc::c_void>;
 
+    /// Enables or disables the frame callbacks of a wayland window.
+    ///
+    /// While enabled, `WindowEvent::FrameReady` is emitted whenever the compositor signals that
+    /// it is a good time to draw a new frame, which allows pacing the rendering to the display
+    /// and not drawing at all while the window is hidden. The first `FrameReady` is emitted right
+    /// away, the next ones follow the frames committed by the application.
+    ///
+    /// Has no effect if the window doesn't use wayland.
+    fn set_wayland_frame_callbacks(&self, enabled: bool);
+
+    /// Returns `true` if the frame callbacks of this window are enabled.
+    ///
+    /// Always returns `false` if the window doesn't use wayland.
+    fn get_wayland_frame_callbacks(&self) -> bool;
+
     /// Check if the window is ready for drawing
     ///
     /// It is a remnant of a previous implementation detail for the
@@ -191,6 +236,21 @@ impl WindowExt for Window {
         }
     }
 
+    #[inline]
+    fn set_wayland_frame_callbacks(&self, enabled: bool) {
+        if let LinuxWindow::Wayland(ref w) = self.window {
+            w.set_frame_callbacks(enabled);
+        }
+    }
+
+    #[inline]
+    fn get_wayland_frame_callbacks(&self) -> bool {
+        match self.window {
+            LinuxWindow::Wayland(ref w) => w.get_frame_callbacks(),
+            _ => false
+        }
+    }
+
     #[inline]
     fn is_ready(&self) -> bool {
         true
@@ -5,7 +5,7 @@ extern crate android_glue;
 use libc;
 use std::sync::mpsc::{Receiver, channel};
 use std::os::raw::c_void;
-use {CreationError, Event, WindowEvent, MouseCursor};
+use {CreationError, Event, Fullscreen, WindowEvent, MouseCursor};
 use CreationError::OsError;
 use WindowId as RootWindowId;
 use events::{Touch, TouchPhase};
@@ -24,12 +24,12 @@ pub struct EventsLoop {
 pub struct EventsLoopProxy;
 
 impl EventsLoop {
-    pub fn new() -> EventsLoop {
+    pub fn new() -> Result<EventsLoop, ::EventsLoopCreationError> {
         let (tx, rx) = channel();
         android_glue::add_sender(tx);
-        EventsLoop {
+        Ok(EventsLoop {
             event_rx: rx,
-        }
+        })
     }
 
     #[inline]
@@ -44,68 +44,30 @@ impl EventsLoop {
         MonitorId
     }
 
+    pub fn get_available_devices(&self) -> VecDeque<DeviceId> {
+        VecDeque::new()
+    }
+
+    pub fn get_device_info(&self, _: &DeviceId) -> Option<::DeviceInfo> {
+        None
+    }
   
     pub fn poll_events<F>(&mut self, mut callback: F)
         where F: FnMut(::Event)
     {
         while let Ok(event) = self.event_rx.try_recv() {
+            process_event(event, &mut callback);
+        }
+    }
 
-            let e = match event{
-                android_glue::Event::EventMotion(motion) => {
-                    Some(Event::WindowEvent {
-                        window_id: RootWindowId(WindowId),
-                        event: WindowEvent::Touch(Touch {
-                            phase: match motion.action {
-                                android_glue::MotionAction::Down => TouchPhase::Started,
-                                android_glue::MotionAction::Move => TouchPhase::Moved,
-                                android_glue::MotionAction::Up => TouchPhase::Ended,
-                                android_glue::MotionAction::Cancel => TouchPhase::Cancelled,
-                            },
-                            location: (motion.x as f64, motion.y as f64),
-                            id: motion.pointer_id as u64,
-                            device_id: DEVICE_ID,
-                        }),
-                    })
-                },
-                android_glue::Event::InitWindow => {
-                    // The activity went to foreground.
-                    Some(Event::Suspended(false))
-                },
-                android_glue::Event::TermWindow => {
-                    // The activity went to background.
-                    Some(Event::Suspended(true))
-                },
-                android_glue::Event::WindowResized |
-                android_glue::Event::ConfigChanged => {
-                    // Activity Orientation changed or resized.
-                    let native_window = unsafe { android_glue::get_native_window() };
-                    if native_window.is_null() {
-                        None
-                    } else {
-                        let w = unsafe { ffi::ANativeWindow_getWidth(native_window as *const _) } as u32;
-                        let h = unsafe { ffi::ANativeWindow_getHeight(native_window as *const _) } as u32;
-                        Some(Event::WindowEvent {
-                            window_id: RootWindowId(WindowId),
-                            event: WindowEvent::Resized(w, h),
-                        })
-                    }
-                },
-                android_glue::Event::WindowRedrawNeeded => {
-                    // The activity needs to be redrawn.
-                    Some(Event::WindowEvent {
-                        window_id: RootWindowId(WindowId),
-                        event: WindowEvent::Refresh,
-                    })
-                }
-                _ => {
-                    None
-                }
-            };
-            
-            if let Some(event) = e {
-                callback(event);
-            }
-        };
+    pub fn wait_events_timeout<F>(&mut self, timeout: ::std::time::Duration, mut callback: F)
+        where F: FnMut(::Event)
+    {
+        // the events that arrived along with the first one are processed too
+        if let Ok(event) = self.event_rx.recv_timeout(timeout) {
+            process_event(event, &mut callback);
+            self.poll_events(callback);
+        }
     }
 
     pub fn run_forever<F>(&mut self, mut callback: F)
@@ -131,6 +93,70 @@ impl EventsLoop {
     }
 }
 
+// Converts an event of the activity and passes it to the callback.
+fn process_event<F>(event: android_glue::Event, callback: &mut F)
+    where F: FnMut(::Event)
+{
+    let e = match event{
+        android_glue::Event::EventMotion(motion) => {
+            Some(Event::WindowEvent {
+                window_id: RootWindowId(WindowId),
+                event: WindowEvent::Touch(Touch {
+                    phase: match motion.action {
+                        android_glue::MotionAction::Down => TouchPhase::Started,
+                        android_glue::MotionAction::Move => TouchPhase::Moved,
+                        android_glue::MotionAction::Up => TouchPhase::Ended,
+                        android_glue::MotionAction::Cancel => TouchPhase::Cancelled,
+                    },
+                    location: (motion.x as f64, motion.y as f64),
+                    id: motion.pointer_id as u64,
+                    device_id: DEVICE_ID,
+                    force: None,
+                    radius: None,
+                    orientation: None,
+                }),
+            })
+        },
+        android_glue::Event::InitWindow => {
+            // The activity went to foreground.
+            Some(Event::Suspended(false))
+        },
+        android_glue::Event::TermWindow => {
+            // The activity went to background.
+            Some(Event::Suspended(true))
+        },
+        android_glue::Event::WindowResized |
+        android_glue::Event::ConfigChanged => {
+            // Activity Orientation changed or resized.
+            let native_window = unsafe { android_glue::get_native_window() };
+            if native_window.is_null() {
+                None
+            } else {
+                let w = unsafe { ffi::ANativeWindow_getWidth(native_window as *const _) } as u32;
+                let h = unsafe { ffi::ANativeWindow_getHeight(native_window as *const _) } as u32;
+                Some(Event::WindowEvent {
+                    window_id: RootWindowId(WindowId),
+                    event: WindowEvent::Resized(w, h),
+                })
+            }
+        },
+        android_glue::Event::WindowRedrawNeeded => {
+            // The activity needs to be redrawn.
+            Some(Event::WindowEvent {
+                window_id: RootWindowId(WindowId),
+                event: WindowEvent::Refresh,
+            })
+        }
+        _ => {
+            None
+        }
+    };
+
+    if let Some(event) = e {
+        callback(event);
+    }
+}
+
 impl EventsLoopProxy {
     pub fn wakeup(&self) -> Result<(), ::EventsLoopClosed> {
         android_glue::wake_event_loop();
@@ -174,6 +200,46 @@ impl MonitorId {
     pub fn get_hidpi_factor(&self) -> f32 {
         1.0
     }
+
+    #[inline]
+    pub fn get_physical_size_mm(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    #[inline]
+    pub fn get_refresh_rate(&self) -> Option<u16> {
+        None
+    }
+
+    #[inline]
+    pub fn get_make(&self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    pub fn get_model(&self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    pub fn get_subpixel_layout(&self) -> ::SubpixelLayout {
+        ::SubpixelLayout::Unknown
+    }
+
+    #[inline]
+    pub fn get_rotation(&self) -> ::MonitorRotation {
+        ::MonitorRotation::Normal
+    }
+
+    #[inline]
+    pub fn get_video_modes(&self) -> VecDeque<::VideoMode> {
+        VecDeque::new()
+    }
+
+    #[inline]
+    pub fn get_current_video_mode(&self) -> Option<::VideoMode> {
+        None
+    }
 }
 
 #[derive(Clone, Default)]
@@ -211,6 +277,11 @@ impl Window {
     pub fn set_title(&self, _: &str) {
     }
 
+    #[inline]
+    pub fn request_redraw(&self) {
+        // TODO: android_glue has no way to inject events into the queue
+    }
+
     #[inline]
     pub fn show(&self) {
     }
@@ -284,7 +355,7 @@ impl Window {
     }
 
     #[inline]
-    pub fn set_fullscreen(&self, _monitor: Option<RootMonitorId>) {
+    pub fn set_fullscreen(&self, _fullscreen: Option<Fullscreen>) {
         // Android has single screen maximized apps so nothing to do
     }
 
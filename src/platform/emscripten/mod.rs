@@ -47,6 +47,46 @@ impl MonitorId {
     pub fn get_hidpi_factor(&self) -> f32 {
         1.0
     }
+
+    #[inline]
+    pub fn get_physical_size_mm(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    #[inline]
+    pub fn get_refresh_rate(&self) -> Option<u16> {
+        None
+    }
+
+    #[inline]
+    pub fn get_make(&self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    pub fn get_model(&self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    pub fn get_subpixel_layout(&self) -> ::SubpixelLayout {
+        ::SubpixelLayout::Unknown
+    }
+
+    #[inline]
+    pub fn get_rotation(&self) -> ::MonitorRotation {
+        ::MonitorRotation::Normal
+    }
+
+    #[inline]
+    pub fn get_video_modes(&self) -> VecDeque<::VideoMode> {
+        VecDeque::new()
+    }
+
+    #[inline]
+    pub fn get_current_video_mode(&self) -> Option<::VideoMode> {
+        None
+    }
 }
 
 // Used to assign a callback to emscripten main loop
@@ -83,11 +123,11 @@ pub struct EventsLoop {
 }
 
 impl EventsLoop {
-    pub fn new() -> EventsLoop {
-        EventsLoop {
+    pub fn new() -> Result<EventsLoop, ::EventsLoopCreationError> {
+        Ok(EventsLoop {
             window: Mutex::new(None),
             interrupted: AtomicBool::new(false),
-        }
+        })
     }
 
     pub fn interrupt(&self) {
@@ -110,6 +150,14 @@ impl EventsLoop {
         MonitorId
     }
 
+    pub fn get_available_devices(&self) -> VecDeque<DeviceId> {
+        VecDeque::new()
+    }
+
+    pub fn get_device_info(&self, _: &DeviceId) -> Option<::DeviceInfo> {
+        None
+    }
+
     pub fn poll_events<F>(&self, mut callback: F)
         where F: FnMut(::Event)
     {
@@ -121,6 +169,13 @@ impl EventsLoop {
         }
     }
 
+    pub fn wait_events_timeout<F>(&self, _timeout: ::std::time::Duration, callback: F)
+        where F: FnMut(::Event)
+    {
+        // Blocking the browser's main thread would prevent any event from being received.
+        self.poll_events(callback);
+    }
+
     pub fn run_forever<F>(&self, mut callback: F)
         where F: FnMut(::Event) -> ::ControlFlow
     {
@@ -294,6 +349,20 @@ impl Window {
     pub fn set_title(&self, _title: &str) {
     }
 
+    pub fn request_redraw(&self) {
+        let mut events = self.window.events.borrow_mut();
+        let already_requested = events.iter().any(|event| match *event {
+            ::Event::WindowEvent { event: ::WindowEvent::RedrawRequested, .. } => true,
+            _ => false,
+        });
+        if !already_requested {
+            events.push_back(::Event::WindowEvent {
+                window_id: ::WindowId(WindowId(0)),
+                event: ::WindowEvent::RedrawRequested,
+            });
+        }
+    }
+
     #[inline]
     pub fn get_position(&self) -> Option<(i32, i32)> {
         Some((0, 0))
@@ -360,25 +429,28 @@ impl Window {
             if state == *old_state {
                 return Ok(());
             }
+            if state == Confine {
+                return Err("Cursor cannot be confined on emscripten.".to_string());
+            }
 
             // Set or unset grab callback
             match state {
-                Hide | Normal => em_try(ffi::emscripten_set_pointerlockchange_callback(ptr::null(), 0 as *mut c_void, ffi::EM_FALSE, None))?,
-                Grab => em_try(ffi::emscripten_set_pointerlockchange_callback(ptr::null(), 0 as *mut c_void, ffi::EM_FALSE, Some(pointerlockchange_callback)))?,
+                Hide | Normal | Confine => em_try(ffi::emscripten_set_pointerlockchange_callback(ptr::null(), 0 as *mut c_void, ffi::EM_FALSE, None))?,
+                Grab | Lock => em_try(ffi::emscripten_set_pointerlockchange_callback(ptr::null(), 0 as *mut c_void, ffi::EM_FALSE, Some(pointerlockchange_callback)))?,
             }
 
             // Go back to normal cursor state
             match *old_state {
                 Hide => show_mouse(),
-                Grab => em_try(ffi::emscripten_exit_pointerlock())?,
-                Normal => (),
+                Grab | Lock => em_try(ffi::emscripten_exit_pointerlock())?,
+                Normal | Confine => (),
             }
 
             // Set cursor from normal cursor state
             match state {
                 Hide => ffi::emscripten_hide_mouse(),
-                Grab => em_try(ffi::emscripten_request_pointerlock(ptr::null(), ffi::EM_TRUE))?,
-                Normal => (),
+                Grab | Lock => em_try(ffi::emscripten_request_pointerlock(ptr::null(), ffi::EM_TRUE))?,
+                Normal | Confine => (),
             }
 
             // Update
@@ -404,7 +476,7 @@ impl Window {
     }
 
     #[inline]
-    pub fn set_fullscreen(&self, _monitor: Option<::MonitorId>) {
+    pub fn set_fullscreen(&self, _fullscreen: Option<::Fullscreen>) {
         // iOS has single screen maximized apps so nothing to do
     }
 
@@ -70,7 +70,7 @@ use libc::c_int;
 use objc::runtime::{Class, Object, Sel, BOOL, YES };
 use objc::declare::{ ClassDecl };
 
-use { CreationError, CursorState, MouseCursor, WindowAttributes };
+use { CreationError, CursorState, Fullscreen, MouseCursor, WindowAttributes };
 use WindowId as RootEventId;
 use WindowEvent;
 use Event;
@@ -150,6 +150,46 @@ impl MonitorId {
     pub fn get_hidpi_factor(&self) -> f32 {
         1.0
     }
+
+    #[inline]
+    pub fn get_physical_size_mm(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    #[inline]
+    pub fn get_refresh_rate(&self) -> Option<u16> {
+        None
+    }
+
+    #[inline]
+    pub fn get_make(&self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    pub fn get_model(&self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    pub fn get_subpixel_layout(&self) -> ::SubpixelLayout {
+        ::SubpixelLayout::Unknown
+    }
+
+    #[inline]
+    pub fn get_rotation(&self) -> ::MonitorRotation {
+        ::MonitorRotation::Normal
+    }
+
+    #[inline]
+    pub fn get_video_modes(&self) -> VecDeque<::VideoMode> {
+        VecDeque::new()
+    }
+
+    #[inline]
+    pub fn get_current_video_mode(&self) -> Option<::VideoMode> {
+        None
+    }
 }
 
 pub struct EventsLoop {
@@ -160,7 +200,7 @@ pub struct EventsLoop {
 pub struct EventsLoopProxy;
 
 impl EventsLoop {
-    pub fn new() -> EventsLoop {
+    pub fn new() -> Result<EventsLoop, ::EventsLoopCreationError> {
         unsafe {
             if setjmp(mem::transmute(&mut jmpbuf)) != 0 {
                 let app: id = msg_send![Class::get("UIApplication").unwrap(), sharedApplication];
@@ -172,7 +212,7 @@ impl EventsLoop {
                     delegate_state: state
                 };
 
-                return events_loop;
+                return Ok(events_loop);
             }
         }
 
@@ -180,7 +220,7 @@ impl EventsLoop {
         create_view_class();
         start_app();
 
-        panic!("Couldn't create UIApplication")
+        Err(::EventsLoopCreationError::OsError("Couldn't create UIApplication".to_owned()))
     }
 
     #[inline]
@@ -195,7 +235,30 @@ impl EventsLoop {
         MonitorId
     }
 
-    pub fn poll_events<F>(&mut self, mut callback: F)
+    pub fn get_available_devices(&self) -> VecDeque<DeviceId> {
+        VecDeque::new()
+    }
+
+    pub fn get_device_info(&self, _: &DeviceId) -> Option<::DeviceInfo> {
+        None
+    }
+
+    pub fn poll_events<F>(&mut self, callback: F)
+        where F: FnMut(::Event)
+    {
+        self.run_runloop(0.000002, callback)
+    }
+
+    pub fn wait_events_timeout<F>(&mut self, timeout: ::std::time::Duration, callback: F)
+        where F: FnMut(::Event)
+    {
+        let seconds = timeout.as_secs() as CFTimeInterval + timeout.subsec_nanos() as CFTimeInterval * 1e-9;
+        self.run_runloop(seconds, callback)
+    }
+
+    // Runs the runloop until it handled a source or `timeout` passed, then processes the sources
+    // that are ready and delivers the next event.
+    fn run_runloop<F>(&mut self, timeout: CFTimeInterval, mut callback: F)
         where F: FnMut(::Event)
     {
         unsafe {
@@ -215,8 +278,10 @@ impl EventsLoop {
             }
 
             // run runloop
-            let seconds: CFTimeInterval = 0.000002;
-            while CFRunLoopRunInMode(kCFRunLoopDefaultMode, seconds, 1) == kCFRunLoopRunHandledSource {}
+            if CFRunLoopRunInMode(kCFRunLoopDefaultMode, timeout, 1) == kCFRunLoopRunHandledSource {
+                let seconds: CFTimeInterval = 0.000002;
+                while CFRunLoopRunInMode(kCFRunLoopDefaultMode, seconds, 1) == kCFRunLoopRunHandledSource {}
+            }
 
             if let Some(event) = state.events_queue.pop_front() {
                 callback(event)
@@ -275,6 +340,22 @@ impl Window {
     pub fn set_title(&self, _: &str) {
     }
 
+    pub fn request_redraw(&self) {
+        unsafe {
+            let state = &mut *self.delegate_state;
+            let already_requested = state.events_queue.iter().any(|event| match *event {
+                Event::WindowEvent { event: WindowEvent::RedrawRequested, .. } => true,
+                _ => false,
+            });
+            if !already_requested {
+                state.events_queue.push_back(Event::WindowEvent {
+                    window_id: RootEventId(WindowId),
+                    event: WindowEvent::RedrawRequested,
+                });
+            }
+        }
+    }
+
     #[inline]
     pub fn show(&self) {
     }
@@ -350,7 +431,7 @@ impl Window {
     }
 
     #[inline]
-    pub fn set_fullscreen(&self, _monitor: Option<RootMonitorId>) {
+    pub fn set_fullscreen(&self, _fullscreen: Option<Fullscreen>) {
         // iOS has single screen maximized apps so nothing to do
     }
 
@@ -464,6 +545,16 @@ fn create_delegate_class() {
                 let location: CGPoint = msg_send![touch, locationInView:nil];
                 let touch_id = touch as u64;
                 let phase: i32 = msg_send![touch, phase];
+                let major_radius: CGFloat = msg_send![touch, majorRadius];
+                // the force is only available since iOS 9
+                let has_force: BOOL = msg_send![touch, respondsToSelector:sel!(force)];
+                let force = if has_force == YES {
+                    let force: CGFloat = msg_send![touch, force];
+                    let maximum_force: CGFloat = msg_send![touch, maximumPossibleForce];
+                    if maximum_force > 0.0 { Some((force / maximum_force) as f64) } else { None }
+                } else {
+                    None
+                };
 
                 state.events_queue.push_back(Event::WindowEvent {
                     window_id: RootEventId(WindowId),
@@ -478,7 +569,10 @@ fn create_delegate_class() {
                             3 => TouchPhase::Ended,
                             4 => TouchPhase::Cancelled,
                             _ => panic!("unexpected touch phase: {:?}", phase)
-                        }
+                        },
+                        force: force,
+                        radius: Some((major_radius as f64, major_radius as f64)),
+                        orientation: None,
                     }),
                 });
             }
@@ -1,17 +1,19 @@
 #![cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "openbsd"))]
 
 use std::collections::VecDeque;
+use std::os::unix::io::RawFd;
 use std::sync::Arc;
-use std::env;
+use std::time::{Duration, Instant};
+use std::{env, io};
 
-use {CreationError, CursorState, EventsLoopClosed, MouseCursor, ControlFlow};
+use {CreationError, CursorState, EventsLoopClosed, EventsLoopCreationError, Fullscreen, MouseCursor, ControlFlow};
 use libc;
 
 use self::x11::XConnection;
-use self::x11::XError;
 use self::x11::ffi::XVisualInfo;
 
-pub use self::x11::XNotSupported;
+pub use self::x11::{XError, XNotSupported};
+pub use self::wayland::WaylandError;
 use window::MonitorId as RootMonitorId;
 
 mod dlopen;
@@ -24,7 +26,7 @@ pub mod x11;
 /// will be tried by winit. If it is not set, winit will try to connect to a wayland connection,
 /// and if it fails will fallback on x11.
 ///
-/// If this variable is set with any other value, winit will panic.
+/// If this variable is set with any other value, creating the events loop will fail.
 const BACKEND_PREFERENCE_ENV_VAR: &str = "WINIT_UNIX_BACKEND";
 
 #[derive(Clone, Default)]
@@ -102,6 +104,70 @@ impl MonitorId {
             &MonitorId::Wayland(ref m) => m.get_hidpi_factor(),
         }
     }
+
+    #[inline]
+    pub fn get_physical_size_mm(&self) -> Option<(u32, u32)> {
+        match self {
+            &MonitorId::X(ref m) => m.get_physical_size_mm(),
+            &MonitorId::Wayland(ref m) => m.get_physical_size_mm(),
+        }
+    }
+
+    #[inline]
+    pub fn get_refresh_rate(&self) -> Option<u16> {
+        match self {
+            &MonitorId::X(ref m) => m.get_refresh_rate(),
+            &MonitorId::Wayland(ref m) => m.get_refresh_rate(),
+        }
+    }
+
+    #[inline]
+    pub fn get_make(&self) -> Option<String> {
+        match self {
+            &MonitorId::X(ref m) => m.get_make(),
+            &MonitorId::Wayland(ref m) => m.get_make(),
+        }
+    }
+
+    #[inline]
+    pub fn get_model(&self) -> Option<String> {
+        match self {
+            &MonitorId::X(ref m) => m.get_model(),
+            &MonitorId::Wayland(ref m) => m.get_model(),
+        }
+    }
+
+    #[inline]
+    pub fn get_subpixel_layout(&self) -> ::SubpixelLayout {
+        match self {
+            &MonitorId::X(ref m) => m.get_subpixel_layout(),
+            &MonitorId::Wayland(ref m) => m.get_subpixel_layout(),
+        }
+    }
+
+    #[inline]
+    pub fn get_rotation(&self) -> ::MonitorRotation {
+        match self {
+            &MonitorId::X(ref m) => m.get_rotation(),
+            &MonitorId::Wayland(ref m) => m.get_rotation(),
+        }
+    }
+
+    #[inline]
+    pub fn get_video_modes(&self) -> VecDeque<::VideoMode> {
+        match self {
+            &MonitorId::X(ref m) => m.get_video_modes(),
+            &MonitorId::Wayland(ref m) => m.get_video_modes(),
+        }
+    }
+
+    #[inline]
+    pub fn get_current_video_mode(&self) -> Option<::VideoMode> {
+        match self {
+            &MonitorId::X(ref m) => m.get_current_video_mode(),
+            &MonitorId::Wayland(ref m) => m.get_current_video_mode(),
+        }
+    }
 }
 
 impl Window {
@@ -138,6 +204,14 @@ impl Window {
         }
     }
 
+    #[inline]
+    pub fn request_redraw(&self) {
+        match self {
+            &Window::X(ref w) => w.request_redraw(),
+            &Window::Wayland(ref w) => w.request_redraw()
+        }
+    }
+
     #[inline]
     pub fn show(&self) {
         match self {
@@ -248,15 +322,15 @@ impl Window {
     pub fn set_maximized(&self, maximized: bool) {
         match self {
             &Window::X(ref w) => w.set_maximized(maximized),
-            &Window::Wayland(ref _w) => {},
+            &Window::Wayland(ref w) => w.set_maximized(maximized),
         }
     }
 
     #[inline]
-    pub fn set_fullscreen(&self, monitor: Option<RootMonitorId>) {
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
         match self {
-            &Window::X(ref w) => w.set_fullscreen(monitor),
-            &Window::Wayland(ref _w) => {},
+            &Window::X(ref w) => w.set_fullscreen(fullscreen),
+            &Window::Wayland(ref w) => w.set_fullscreen(fullscreen),
         }
     }
 
@@ -292,6 +366,35 @@ unsafe extern "C" fn x_error_callback(dpy: *mut x11::ffi::Display, event: *mut x
     0
 }
 
+/// Blocks until `fd` has data available for reading or `timeout` elapses.
+///
+/// Returns `true` if the file descriptor is readable.
+fn wait_for_fd(fd: RawFd, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let now = Instant::now();
+        let remaining = if deadline > now { deadline - now } else { Duration::new(0, 0) };
+        // round up so that we don't wake up slightly before the deadline and spin
+        let millis = remaining.as_secs()
+            .saturating_mul(1000)
+            .saturating_add((remaining.subsec_nanos() as u64 + 999_999) / 1_000_000);
+        let millis = if millis > libc::c_int::max_value() as u64 {
+            libc::c_int::max_value()
+        } else {
+            millis as libc::c_int
+        };
+
+        let mut pollfd = libc::pollfd { fd: fd, events: libc::POLLIN, revents: 0 };
+        match unsafe { libc::poll(&mut pollfd, 1, millis) } {
+            0 => return false,
+            n if n > 0 => return true,
+            _ if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+            _ => return false,
+        }
+    }
+}
+
 pub enum EventsLoop {
     Wayland(wayland::EventsLoop),
     X(x11::EventsLoop)
@@ -304,44 +407,37 @@ pub enum EventsLoopProxy {
 }
 
 impl EventsLoop {
-    pub fn new() -> EventsLoop {
+    pub fn new() -> Result<EventsLoop, EventsLoopCreationError> {
         if let Ok(env_var) = env::var(BACKEND_PREFERENCE_ENV_VAR) {
-            match env_var.as_str() {
-                "x11" => {
-                    return EventsLoop::new_x11().unwrap();      // TODO: propagate
-                },
-                "wayland" => {
-                    match EventsLoop::new_wayland() {
-                        Ok(e) => return e,
-                        Err(_) => panic!()      // TODO: propagate
-                    }
-                },
-                _ => panic!("Unknown environment variable value for {}, try one of `x11`,`wayland`",
-                            BACKEND_PREFERENCE_ENV_VAR),
-            }
+            return match env_var.as_str() {
+                "x11" => EventsLoop::new_x11(),
+                "wayland" => EventsLoop::new_wayland(),
+                _ => Err(EventsLoopCreationError::UnknownBackend(env_var)),
+            };
         }
 
-        if let Ok(el) = EventsLoop::new_wayland() {
-            return el;
-        }
+        let wayland_err = match EventsLoop::new_wayland() {
+            Ok(el) => return Ok(el),
+            Err(err) => err,
+        };
 
-        if let Ok(el) = EventsLoop::new_x11() {
-            return el;
-        }
+        let x11_err = match EventsLoop::new_x11() {
+            Ok(el) => return Ok(el),
+            Err(err) => err,
+        };
 
-        panic!("No backend is available")
+        Err(EventsLoopCreationError::NoBackendAvailable(vec![wayland_err, x11_err]))
     }
 
-    pub fn new_wayland() -> Result<EventsLoop, ()> {
+    pub fn new_wayland() -> Result<EventsLoop, EventsLoopCreationError> {
         wayland::EventsLoop::new()
             .map(EventsLoop::Wayland)
-            .ok_or(())
     }
 
-    pub fn new_x11() -> Result<EventsLoop, XNotSupported> {
+    pub fn new_x11() -> Result<EventsLoop, EventsLoopCreationError> {
         match *X11_BACKEND {
-            Ok(ref x) => Ok(EventsLoop::X(x11::EventsLoop::new(x.clone()))),
-            Err(ref err) => Err(err.clone()),
+            Ok(ref x) => x11::EventsLoop::new(x.clone()).map(EventsLoop::X),
+            Err(ref err) => Err(EventsLoopCreationError::XNotSupported(err.clone())),
         }
     }
 
@@ -367,6 +463,29 @@ impl EventsLoop {
         }
     }
 
+    #[inline]
+    pub fn get_available_devices(&self) -> VecDeque<DeviceId> {
+        match *self {
+            EventsLoop::Wayland(ref evlp) => evlp.get_available_devices()
+                                    .into_iter()
+                                    .map(DeviceId::Wayland)
+                                    .collect(),
+            EventsLoop::X(ref evlp) => evlp.get_available_devices()
+                                        .into_iter()
+                                        .map(DeviceId::X)
+                                        .collect(),
+        }
+    }
+
+    #[inline]
+    pub fn get_device_info(&self, device: &DeviceId) -> Option<::DeviceInfo> {
+        match (self, device) {
+            (&EventsLoop::Wayland(ref evlp), &DeviceId::Wayland(ref device)) => evlp.get_device_info(device),
+            (&EventsLoop::X(ref evlp), &DeviceId::X(ref device)) => evlp.get_device_info(device),
+            _ => None,
+        }
+    }
+
     pub fn create_proxy(&self) -> EventsLoopProxy {
         match *self {
             EventsLoop::Wayland(ref evlp) => EventsLoopProxy::Wayland(evlp.create_proxy()),
@@ -383,6 +502,15 @@ impl EventsLoop {
         }
     }
 
+    pub fn wait_events_timeout<F>(&mut self, timeout: Duration, callback: F)
+        where F: FnMut(::Event)
+    {
+        match *self {
+            EventsLoop::Wayland(ref mut evlp) => evlp.wait_events_timeout(timeout, callback),
+            EventsLoop::X(ref mut evlp) => evlp.wait_events_timeout(timeout, callback)
+        }
+    }
+
     pub fn run_forever<F>(&mut self, callback: F)
         where F: FnMut(::Event) -> ControlFlow
     {
@@ -392,6 +520,23 @@ impl EventsLoop {
         }
     }
 
+    pub fn dispatch_pending<F>(&mut self, callback: F)
+        where F: FnMut(::Event)
+    {
+        match *self {
+            EventsLoop::Wayland(ref mut evlp) => evlp.dispatch_pending(callback),
+            EventsLoop::X(ref mut evlp) => evlp.dispatch_pending(callback)
+        }
+    }
+
+    #[inline]
+    pub fn get_fd(&self) -> RawFd {
+        match *self {
+            EventsLoop::Wayland(ref evlp) => evlp.get_fd(),
+            EventsLoop::X(ref evlp) => evlp.get_fd()
+        }
+    }
+
     #[inline]
     pub fn is_wayland(&self) -> bool {
         match *self {
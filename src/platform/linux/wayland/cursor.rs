@@ -0,0 +1,311 @@
+use std::env;
+
+use MouseCursor;
+
+use super::WindowId;
+
+use wayland_client::{Proxy, RequestResult};
+use wayland_client::cursor::{is_available, load_theme, CursorTheme};
+use wayland_client::protocol::{wl_compositor, wl_pointer, wl_shm, wl_surface};
+
+use super::wayland_protocols::unstable::pointer_constraints::v1::client::{zwp_confined_pointer_v1,
+                                                                          zwp_locked_pointer_v1,
+                                                                          zwp_pointer_constraints_v1};
+
+// Size of the cursor images when `XCURSOR_SIZE` is not set, before scaling.
+const DEFAULT_CURSOR_SIZE: u32 = 24;
+
+/// The cursors of the pointers, drawn with the images of the user's XCursor theme, and the pointer
+/// constraints of the windows that grab them.
+///
+/// It is shared between the pointers, which set the cursor of a window when entering it, and the
+/// windows, which change their cursor while a pointer is over them.
+pub struct CursorManager {
+    compositor: wl_compositor::WlCompositor,
+    shm: wl_shm::WlShm,
+    theme_name: Option<String>,
+    size: u32,
+    // the theme is loaded again when the scale of the cursor changes
+    theme: Option<(CursorTheme, i32)>,
+    pointer_constraints: Option<zwp_pointer_constraints_v1::ZwpPointerConstraintsV1>,
+    pointers: Vec<PointerCursor>,
+    // the surfaces of the windows that grab the pointers, and whether they are locked rather than
+    // confined
+    grabs: Vec<(wl_surface::WlSurface, bool)>,
+}
+
+// The cursor of a pointer, drawn on a surface of its own.
+struct PointerCursor {
+    pointer: wl_pointer::WlPointer,
+    surface: wl_surface::WlSurface,
+    // the window under the pointer, and the serial of the `enter` event
+    focus: Option<(WindowId, u32)>,
+    // the constraints of the pointer, by surface of the grabbing window
+    constraints: Vec<(wl_surface::WlSurface, Constraint)>,
+}
+
+enum Constraint {
+    Locked(zwp_locked_pointer_v1::ZwpLockedPointerV1),
+    Confined(zwp_confined_pointer_v1::ZwpConfinedPointerV1),
+}
+
+impl Constraint {
+    fn destroy(&self) {
+        match *self {
+            Constraint::Locked(ref locked_pointer) => { locked_pointer.destroy(); },
+            Constraint::Confined(ref confined_pointer) => { confined_pointer.destroy(); },
+        }
+    }
+}
+
+impl CursorManager {
+    pub fn new(compositor: wl_compositor::WlCompositor, shm: wl_shm::WlShm,
+               pointer_constraints: Option<zwp_pointer_constraints_v1::ZwpPointerConstraintsV1>)
+        -> CursorManager
+    {
+        let size = env::var("XCURSOR_SIZE").ok()
+            .and_then(|size| size.parse().ok())
+            .unwrap_or(DEFAULT_CURSOR_SIZE);
+        CursorManager {
+            compositor: compositor,
+            shm: shm,
+            theme_name: env::var("XCURSOR_THEME").ok(),
+            size: size,
+            theme: None,
+            pointer_constraints: pointer_constraints,
+            pointers: Vec::new(),
+            grabs: Vec::new(),
+        }
+    }
+
+    pub fn add_pointer(&mut self, pointer: &wl_pointer::WlPointer) {
+        let mut constraints = Vec::new();
+        if let Some(ref pointer_constraints) = self.pointer_constraints {
+            for &(ref surface, lock) in &self.grabs {
+                if let Some(constraint) = constrain_pointer(pointer_constraints, pointer, surface, lock) {
+                    constraints.push((surface.clone().unwrap(), constraint));
+                }
+            }
+        }
+        self.pointers.push(PointerCursor {
+            pointer: pointer.clone().unwrap(),
+            surface: self.compositor.create_surface(),
+            focus: None,
+            constraints: constraints,
+        });
+    }
+
+    pub fn remove_pointer(&mut self, pointer: &wl_pointer::WlPointer) {
+        self.pointers.retain(|cursor| {
+            if !cursor.pointer.equals(pointer) {
+                return true;
+            }
+            for &(_, ref constraint) in &cursor.constraints {
+                constraint.destroy();
+            }
+            cursor.surface.destroy();
+            false
+        });
+    }
+
+    /// Locks the pointers in place, or confines them, while they are over `surface`.
+    ///
+    /// Returns an error if the compositor doesn't support pointer constraints.
+    pub fn grab(&mut self, surface: &wl_surface::WlSurface, lock: bool) -> Result<(), String> {
+        let pointer_constraints = match self.pointer_constraints {
+            Some(ref pointer_constraints) => pointer_constraints,
+            None => return Err("Cursor cannot be grabbed: the compositor doesn't support pointer constraints.".to_string())
+        };
+        for cursor in &mut self.pointers {
+            if let Some(constraint) = constrain_pointer(pointer_constraints, &cursor.pointer, surface, lock) {
+                cursor.constraints.push((surface.clone().unwrap(), constraint));
+            }
+        }
+        self.grabs.push((surface.clone().unwrap(), lock));
+        Ok(())
+    }
+
+    pub fn ungrab(&mut self, surface: &wl_surface::WlSurface) {
+        for cursor in &mut self.pointers {
+            cursor.constraints.retain(|&(ref grab_surface, ref constraint)| {
+                if !grab_surface.equals(surface) {
+                    return true;
+                }
+                constraint.destroy();
+                false
+            });
+        }
+        self.grabs.retain(|&(ref grab_surface, _)| !grab_surface.equals(surface));
+    }
+
+    /// A pointer entered a window, whose cursor is set right away.
+    pub fn enter(&mut self, pointer: &wl_pointer::WlPointer, wid: WindowId, serial: u32,
+                 cursor: MouseCursor, scale: i32)
+    {
+        if let Some(index) = self.pointers.iter().position(|c| c.pointer.equals(pointer)) {
+            self.pointers[index].focus = Some((wid, serial));
+            self.apply(index, cursor, scale);
+        }
+    }
+
+    pub fn leave(&mut self, pointer: &wl_pointer::WlPointer) {
+        for cursor in &mut self.pointers {
+            if cursor.pointer.equals(pointer) {
+                cursor.focus = None;
+            }
+        }
+    }
+
+    /// Changes the cursor of a window, which is only visible while a pointer is over it.
+    pub fn set_cursor(&mut self, wid: WindowId, cursor: MouseCursor, scale: i32) {
+        for index in 0..self.pointers.len() {
+            match self.pointers[index].focus {
+                Some((focus, _)) if focus == wid => self.apply(index, cursor, scale),
+                _ => ()
+            }
+        }
+    }
+
+    fn apply(&mut self, index: usize, cursor: MouseCursor, scale: i32) {
+        let serial = match self.pointers[index].focus {
+            Some((_, serial)) => serial,
+            None => return
+        };
+
+        if cursor == MouseCursor::NoneCursor {
+            self.pointers[index].pointer.set_cursor(serial, None, 0, 0);
+            return;
+        }
+
+        if !is_available() {
+            // the default cursor of the compositor is kept
+            return;
+        }
+
+        let scale = if scale < 1 { 1 } else { scale };
+        if self.theme.as_ref().map(|&(_, theme_scale)| theme_scale != scale).unwrap_or(true) {
+            let theme = load_theme(
+                self.theme_name.as_ref().map(|name| &name[..]),
+                self.size * scale as u32,
+                &self.shm
+            );
+            self.theme = Some((theme, scale));
+        }
+
+        let theme = &self.theme.as_ref().unwrap().0;
+        let PointerCursor { ref pointer, ref surface, .. } = self.pointers[index];
+
+        // Try the names in order, and fall back to the default cursor if the theme has none of them.
+        let cursor = match cursor_names(cursor).iter().chain(&["left_ptr"])
+            .filter_map(|name| theme.get_cursor(name))
+            .next()
+        {
+            Some(cursor) => cursor,
+            None => return
+        };
+        let buffer = match cursor.frame_buffer(0) {
+            Some(buffer) => buffer,
+            None => return
+        };
+        let (w, h, hx, hy) = cursor.frame_info(0)
+            .map(|(w, h, hx, hy, _)| (w as i32, h as i32, hx as i32, hy as i32))
+            .unwrap_or((0, 0, 0, 0));
+
+        if surface.version() >= 3 {
+            surface.set_buffer_scale(scale);
+        }
+        surface.attach(Some(&buffer), 0, 0);
+        if surface.version() >= 4 {
+            surface.damage_buffer(0, 0, w, h);
+        } else {
+            surface.damage(0, 0, w / scale, h / scale);
+        }
+        surface.commit();
+        // the hotspot is in surface coordinates
+        pointer.set_cursor(serial, Some(surface), hx / scale, hy / scale);
+    }
+}
+
+// The constraint is only active while the pointer is over the surface, and comes back when the
+// pointer enters it again.
+fn constrain_pointer(pointer_constraints: &zwp_pointer_constraints_v1::ZwpPointerConstraintsV1,
+                     pointer: &wl_pointer::WlPointer, surface: &wl_surface::WlSurface, lock: bool)
+    -> Option<Constraint>
+{
+    let lifetime = zwp_pointer_constraints_v1::Lifetime::Persistent.to_raw();
+    if lock {
+        match pointer_constraints.lock_pointer(surface, pointer, None, lifetime) {
+            RequestResult::Sent(locked_pointer) => Some(Constraint::Locked(locked_pointer)),
+            RequestResult::Destroyed => None
+        }
+    } else {
+        match pointer_constraints.confine_pointer(surface, pointer, None, lifetime) {
+            RequestResult::Sent(confined_pointer) => Some(Constraint::Confined(confined_pointer)),
+            RequestResult::Destroyed => None
+        }
+    }
+}
+
+// The names of the theme's cursors for a `MouseCursor`, matching those used on X11.
+//
+// Try multiple names in some cases where the name differs on the desktop environments or themes.
+// The better looking (or more suiting) names come first.
+fn cursor_names(cursor: MouseCursor) -> &'static [&'static str] {
+    match cursor {
+        MouseCursor::Alias => &["link"],
+        MouseCursor::Arrow => &["arrow"],
+        MouseCursor::Cell => &["plus"],
+        MouseCursor::Copy => &["copy"],
+        MouseCursor::Crosshair => &["crosshair"],
+        MouseCursor::Default => &["left_ptr"],
+        MouseCursor::Hand => &["hand2", "hand1"],
+        MouseCursor::Help => &["question_arrow"],
+        MouseCursor::Move => &["move"],
+        MouseCursor::Grab => &["openhand", "grab"],
+        MouseCursor::Grabbing => &["closedhand", "grabbing"],
+        MouseCursor::Progress => &["left_ptr_watch"],
+        MouseCursor::AllScroll => &["all-scroll"],
+        MouseCursor::ContextMenu => &["context-menu"],
+
+        MouseCursor::NoDrop => &["no-drop", "circle"],
+        MouseCursor::NotAllowed => &["crossed_circle"],
+
+        // Resize cursors
+        MouseCursor::EResize => &["right_side"],
+        MouseCursor::NResize => &["top_side"],
+        MouseCursor::NeResize => &["top_right_corner"],
+        MouseCursor::NwResize => &["top_left_corner"],
+        MouseCursor::SResize => &["bottom_side"],
+        MouseCursor::SeResize => &["bottom_right_corner"],
+        MouseCursor::SwResize => &["bottom_left_corner"],
+        MouseCursor::WResize => &["left_side"],
+        MouseCursor::EwResize => &["h_double_arrow"],
+        MouseCursor::NsResize => &["v_double_arrow"],
+        MouseCursor::NwseResize => &["bd_double_arrow", "size_bdiag"],
+        MouseCursor::NeswResize => &["fd_double_arrow", "size_fdiag"],
+        MouseCursor::ColResize => &["split_h", "h_double_arrow"],
+        MouseCursor::RowResize => &["split_v", "v_double_arrow"],
+
+        MouseCursor::Text => &["text", "xterm"],
+        MouseCursor::VerticalText => &["vertical-text"],
+
+        MouseCursor::Wait => &["watch"],
+
+        MouseCursor::ZoomIn => &["zoom-in"],
+        MouseCursor::ZoomOut => &["zoom-out"],
+
+        // hidden by attaching no surface to the pointer
+        MouseCursor::NoneCursor => &[],
+    }
+}
+
+impl Drop for CursorManager {
+    fn drop(&mut self) {
+        for cursor in &self.pointers {
+            for &(_, ref constraint) in &cursor.constraints {
+                constraint.destroy();
+            }
+            cursor.surface.destroy();
+        }
+    }
+}
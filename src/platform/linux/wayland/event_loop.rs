@@ -1,21 +1,33 @@
 use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::mem;
 use std::sync::{Arc, Mutex, Weak};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::os::unix::io::RawFd;
+use std::time::Duration;
 
-use {EventsLoopClosed, ControlFlow};
+use {EventsLoopClosed, EventsLoopCreationError, ControlFlow, MonitorRotation, SubpixelLayout, VideoMode};
+use platform::MonitorId as PlatformMonitorId;
+use window::MonitorId as RootMonitorId;
 
-use super::WindowId;
-use super::window::WindowStore;
+use super::{WaylandError, WindowId, DeviceId, Capability, make_did};
+use super::cursor::CursorManager;
+use super::window::{WindowStore, frame_callback_impl};
 use super::keyboard::init_keyboard;
 
-use wayland_client::{EnvHandler, EnvNotify, default_connect, EventQueue, EventQueueHandle, Proxy, StateToken};
+use wayland_client::{EnvHandler, EnvNotify, default_connect, ConnectError, EventQueue, EventQueueHandle, Proxy,
+                     RequestResult, StateToken};
 use wayland_client::protocol::{wl_compositor, wl_seat, wl_shell, wl_shm, wl_subcompositor,
                                wl_display, wl_registry, wl_output, wl_surface,
                                wl_pointer, wl_keyboard, wl_touch};
 
 use super::wayland_window::{Frame, Shell, create_frame, FrameImplementation};
 use super::wayland_protocols::unstable::xdg_shell::v6::client::zxdg_shell_v6;
+use super::wayland_protocols::unstable::pointer_constraints::v1::client::zwp_pointer_constraints_v1;
+use super::wayland_protocols::unstable::relative_pointer::v1::client::{zwp_relative_pointer_manager_v1,
+                                                                       zwp_relative_pointer_v1};
+use super::wayland_protocols::unstable::tablet::v2::client::{zwp_tablet_manager_v2, zwp_tablet_seat_v2,
+                                                             zwp_tablet_v2};
 
 pub struct EventsLoopSink {
     buffer: VecDeque<::Event>
@@ -42,6 +54,10 @@ impl EventsLoopSink {
         self.buffer.push_back(evt);
     }
 
+    fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
     fn empty_with<F>(&mut self, callback: &mut F) where F: FnMut(::Event) {
         for evt in self.buffer.drain(..) {
             callback(evt)
@@ -66,6 +82,8 @@ pub struct EventsLoop {
     pub cleanup_needed: Arc<Mutex<bool>>,
     // The wayland display
     pub display: Arc<wl_display::WlDisplay>,
+    // The cursor of the pointer, shared with the windows
+    pub cursor_manager: Arc<Mutex<CursorManager>>,
 }
 
 // A handle that can be sent across threads and used to wake up the `EventsLoop`.
@@ -99,10 +117,15 @@ impl EventsLoopProxy {
 }
 
 impl EventsLoop {
-    pub fn new() -> Option<EventsLoop> {
+    pub fn new() -> Result<EventsLoop, EventsLoopCreationError> {
         let (display, mut event_queue) = match default_connect() {
             Ok(ret) => ret,
-            Err(_) => return None
+            Err(ConnectError::NoWaylandLib) => return Err(EventsLoopCreationError::WaylandConnectionFailed(
+                "the wayland client library could not be loaded".into()
+            )),
+            Err(ConnectError::NoCompositorListening) => return Err(EventsLoopCreationError::WaylandConnectionFailed(
+                "no wayland compositor is listening".into()
+            )),
         };
 
         let registry = display.get_registry();
@@ -117,26 +140,66 @@ impl EventsLoop {
         );
 
         // two round trips to fully initialize
-        event_queue.sync_roundtrip().expect("Wayland connection unexpectedly lost");
-        event_queue.sync_roundtrip().expect("Wayland connection unexpectedly lost");
+        for _ in 0..2 {
+            if let Err(err) = event_queue.sync_roundtrip() {
+                return Err(EventsLoopCreationError::WaylandConnectionFailed(
+                    format!("connection unexpectedly lost during initialization: {}", err)
+                ));
+            }
+        }
 
-        event_queue.state().with_value(&ctxt_token, |proxy, ctxt| {
+        try!(event_queue.state().with_value(&ctxt_token, |proxy, ctxt| {
             ctxt.ensure_shell(proxy.get_mut(&env_token))
-        });
+        }));
 
         let sink = Arc::new(Mutex::new(EventsLoopSink::new()));
 
         let store = event_queue.state().insert(WindowStore::new());
 
-        let seat_idata = SeatIData {
-            sink: sink.clone(),
-            keyboard: None,
-            pointer: None,
-            touch: None,
-            windows_token: store.clone()
+        // bind the optional globals used to grab the pointer, and the tablet manager
+        let (pointer_constraints, relative_pointer_manager, tablet_manager) = {
+            let ctxt_token = ctxt_token.clone();
+            event_queue.state().with_value(&env_token, |proxy, env| {
+                let ctxt = proxy.get(&ctxt_token);
+                let mut pointer_constraints = None;
+                let mut relative_pointer_manager = None;
+                let mut tablet_manager = None;
+                for &(name, ref interface, _) in env.globals() {
+                    if interface == zwp_pointer_constraints_v1::ZwpPointerConstraintsV1::interface_name() {
+                        pointer_constraints = Some(ctxt.registry.bind(1, name));
+                    } else if interface == zwp_relative_pointer_manager_v1::ZwpRelativePointerManagerV1::interface_name() {
+                        relative_pointer_manager = Some(ctxt.registry.bind(1, name));
+                    } else if interface == zwp_tablet_manager_v2::ZwpTabletManagerV2::interface_name() {
+                        tablet_manager = Some(ctxt.registry.bind(1, name));
+                    }
+                }
+                (pointer_constraints, relative_pointer_manager, tablet_manager)
+            })
+        };
+
+        let cursor_manager = {
+            let env = event_queue.state().get(&env_token).clone_inner().unwrap();
+            Arc::new(Mutex::new(CursorManager::new(env.compositor, env.shm, pointer_constraints)))
         };
 
-        let mut me = EventsLoop {
+        // the seats announced from now on are bound as they come, bind those that are already there
+        event_queue.state().get_mut(&ctxt_token).seat_shared = Some(Arc::new(SeatShared {
+            sink: sink.clone(),
+            cursor_manager: cursor_manager.clone(),
+            relative_pointer_manager: relative_pointer_manager,
+            tablet_manager: tablet_manager,
+            windows_token: store.clone(),
+            seat_names: Mutex::new(Vec::new())
+        }));
+        let seats: Vec<(u32, u32)> = event_queue.state().get(&env_token).globals().iter()
+            .filter(|&&(_, ref interface, _)| interface == wl_seat::WlSeat::interface_name())
+            .map(|&(name, _, version)| (name, version))
+            .collect();
+        for (name, version) in seats {
+            bind_seat(&mut event_queue, &ctxt_token, name, version);
+        }
+
+        let me = EventsLoop {
             display: Arc::new(display),
             evq: RefCell::new(event_queue),
             sink: sink,
@@ -144,14 +207,11 @@ impl EventsLoop {
             store: store,
             ctxt_token: ctxt_token,
             env_token: env_token,
-            cleanup_needed: Arc::new(Mutex::new(false))
+            cleanup_needed: Arc::new(Mutex::new(false)),
+            cursor_manager: cursor_manager,
         };
 
-        me.init_seat(|evqh, seat| {
-            evqh.register(seat, seat_implementation(), seat_idata);
-        });
-
-        Some(me)
+        Ok(me)
     }
 
     pub fn create_proxy(&self) -> EventsLoopProxy {
@@ -167,9 +227,6 @@ impl EventsLoop {
         // send pending events to the server
         self.display.flush().expect("Wayland connection lost.");
 
-        // dispatch any pre-buffered events
-        self.sink.lock().unwrap().empty_with(&mut callback);
-
         // try to read pending events
         if let Some(h) = self.evq.get_mut().prepare_read() {
             h.read_events().expect("Wayland connection lost.");
@@ -179,7 +236,48 @@ impl EventsLoop {
         self.post_dispatch_triggers();
 
         // dispatch buffered events to client
-        self.sink.lock().unwrap().empty_with(&mut callback);
+        self.dispatch_batch(&mut callback);
+    }
+
+    /// Processes the available events, then flushes the requests made by the callback so that
+    /// the caller can wait on the display's file descriptor.
+    pub fn dispatch_pending<F>(&mut self, callback: F)
+        where F: FnMut(::Event)
+    {
+        self.poll_events(callback);
+        self.display.flush().expect("Wayland connection lost.");
+    }
+
+    /// Returns the file descriptor of the connection to the compositor.
+    #[inline]
+    pub fn get_fd(&self) -> RawFd {
+        unsafe { self.display.get_fd() }
+    }
+
+    pub fn wait_events_timeout<F>(&mut self, timeout: Duration, mut callback: F)
+        where F: FnMut(::Event)
+    {
+        // send pending events to the server
+        self.display.flush().expect("Wayland connection lost.");
+
+        // there is no need to wait if events are already buffered
+        self.post_dispatch_triggers();
+        let buffered = !self.sink.lock().unwrap().is_empty();
+
+        // `prepare_read` fails if events are already queued, and then there is no need to wait.
+        if let Some(h) = self.evq.get_mut().prepare_read() {
+            if !buffered && super::super::wait_for_fd(self.get_fd(), timeout) {
+                h.read_events().expect("Wayland connection lost.");
+            } else {
+                h.cancel();
+            }
+        }
+        // dispatch wayland events
+        self.evq.get_mut().dispatch_pending().expect("Wayland connection lost.");
+        self.post_dispatch_triggers();
+
+        // dispatch buffered events to client
+        self.dispatch_batch(&mut callback);
     }
 
     pub fn run_forever<F>(&mut self, mut callback: F)
@@ -194,21 +292,20 @@ impl EventsLoop {
             control_flow.set(ControlFlow::Break);
         };
 
-        // dispatch any pre-buffered events
+        // process any pre-buffered events
         self.post_dispatch_triggers();
-        self.sink.lock().unwrap().empty_with(&mut callback);
 
         loop {
-            // dispatch events blocking if needed
-            self.evq.get_mut().dispatch().expect("Wayland connection lost.");
-            self.post_dispatch_triggers();
-
             // empty buffer of events
-            self.sink.lock().unwrap().empty_with(&mut callback);
+            self.dispatch_batch(&mut callback);
 
             if let ControlFlow::Break = control_flow.get() {
                 break;
             }
+
+            // dispatch events blocking if needed
+            self.evq.get_mut().dispatch().expect("Wayland connection lost.");
+            self.post_dispatch_triggers();
         }
     }
 
@@ -233,6 +330,42 @@ impl EventsLoop {
         .map(|m| MonitorId { info: m.clone() })
         .collect()
     }
+
+    pub fn get_available_devices(&self) -> Vec<DeviceId> {
+        let mut guard = self.evq.borrow_mut();
+        let state = guard.state();
+        let state_ctxt = state.get(&self.ctxt_token);
+        let mut devices = Vec::new();
+        for &(_, _, ref idata) in &state_ctxt.seats {
+            let idata = idata.lock().unwrap();
+            let id = match idata.id {
+                Some(id) => id,
+                None => continue,
+            };
+            let capabilities = [
+                (idata.pointer.is_some(), Capability::Pointer),
+                (idata.keyboard.is_some(), Capability::Keyboard),
+                (idata.touch.is_some(), Capability::Touch),
+                (!idata.tablets.is_empty(), Capability::Tablet),
+            ];
+            for &(present, capability) in &capabilities {
+                if present {
+                    devices.push(DeviceId { seat: id, capability: capability });
+                }
+            }
+        }
+        devices
+    }
+
+    pub fn get_device_info(&self, device: &DeviceId) -> Option<::DeviceInfo> {
+        let mut guard = self.evq.borrow_mut();
+        let state = guard.state();
+        let state_ctxt = state.get(&self.ctxt_token);
+        state_ctxt.seats.iter()
+            .map(|&(_, _, ref idata)| idata.lock().unwrap())
+            .find(|idata| idata.id == Some(device.seat))
+            .and_then(|idata| idata.device_info(device.capability))
+    }
 }
 
 /*
@@ -247,7 +380,8 @@ wayland_env!(InnerEnv,
 
 pub struct StateContext {
     registry: wl_registry::WlRegistry,
-    seat: Option<wl_seat::WlSeat>,
+    seats: Vec<(u32, wl_seat::WlSeat, Arc<Mutex<SeatIData>>)>,
+    seat_shared: Option<Arc<SeatShared>>,
     shell: Option<Shell>,
     monitors: Vec<Arc<Mutex<OutputInfo>>>
 }
@@ -256,7 +390,8 @@ impl StateContext {
     fn new(registry: wl_registry::WlRegistry) -> StateContext {
         StateContext {
             registry: registry,
-            seat: None,
+            seats: Vec::new(),
+            seat_shared: None,
             shell: None,
             monitors: Vec::new()
         }
@@ -266,20 +401,20 @@ impl StateContext {
     ///
     /// If a shell is already bound, do nothing. Otherwise,
     /// try to bind wl_shell as a fallback. If this fails,
-    /// panic, as this is a bug from the compositor.
-    fn ensure_shell(&mut self, env: &mut EnvHandler<InnerEnv>) {
+    /// return an error, as this is a bug from the compositor.
+    fn ensure_shell(&mut self, env: &mut EnvHandler<InnerEnv>) -> Result<(), EventsLoopCreationError> {
         if self.shell.is_some() {
-            return;
+            return Ok(());
         }
         // xdg_shell is not available, so initialize wl_shell
         for &(name, ref interface, _) in env.globals() {
             if interface == "wl_shell" {
                 self.shell = Some(Shell::Wl(self.registry.bind::<wl_shell::WlShell>(1, name)));
-                return;
+                return Ok(());
             }
         }
         // This is a compositor bug, it _must_ at least support wl_shell
-        panic!("Compositor didi not advertize xdg_shell not wl_shell.");
+        Err(EventsLoopCreationError::MissingExtension("xdg_shell or wl_shell"))
     }
 
     pub fn monitor_id_for(&self, output: &wl_output::WlOutput) -> MonitorId {
@@ -296,31 +431,11 @@ impl StateContext {
 }
 
 impl EventsLoop {
-    pub fn init_seat<F>(&mut self, f: F)
-    where F: FnOnce(&mut EventQueueHandle, &wl_seat::WlSeat)
-    {
-        let mut guard = self.evq.borrow_mut();
-        if guard.state().get(&self.ctxt_token).seat.is_some() {
-            // seat has already been init
-            return;
-        }
-
-        // clone the token to make borrow checker happy
-        let ctxt_token = self.ctxt_token.clone();
-        let seat = guard.state().with_value(&self.env_token, |proxy, env| {
-            let ctxt = proxy.get(&ctxt_token);
-            for &(name, ref interface, _) in env.globals() {
-                if interface == wl_seat::WlSeat::interface_name() {
-                    return Some(ctxt.registry.bind::<wl_seat::WlSeat>(5, name));
-                }
-            }
-            None
-        });
-
-        if let Some(seat) = seat {
-            f(&mut *guard, &seat);
-            guard.state().get_mut(&self.ctxt_token).seat = Some(seat)
-        }
+    // Delivers the buffered events to the client, surrounded by `NewEvents` and `EventsCleared`.
+    fn dispatch_batch<F>(&mut self, callback: &mut F) where F: FnMut(::Event) {
+        callback(::Event::NewEvents);
+        self.sink.lock().unwrap().empty_with(callback);
+        callback(::Event::EventsCleared);
     }
 
     fn post_dispatch_triggers(&mut self) {
@@ -339,9 +454,21 @@ impl EventsLoop {
                 *cleanup_needed = false;
             }
         }
+        // map or unmap the windows that were shown or hidden
+        let to_map = evq.state().get_mut(&self.store).update_visibility(&self.store);
+        for window in to_map {
+            let (width, height) = window.size;
+            let (implem, idata) = window.frame_implementation();
+            match make_frame(evq, &self.env_token, &self.ctxt_token, &window.surface,
+                             width, height, implem, idata) {
+                Ok(frame) => window.set_frame(frame),
+                Err(err) => window.fail(err),
+            }
+        }
         // process pending resize/refresh
+        let mut redraws = Vec::new();
         evq.state().get_mut(&self.store).for_each(
-            |newsize, refresh, frame_refresh, closed, wid, frame| {
+            |newsize, frame_refresh, redraw, closed, wid, frame| {
                 if let Some(frame) = frame {
                     if let Some((w, h)) = newsize {
                         frame.resize(w as i32, h as i32);
@@ -351,51 +478,74 @@ impl EventsLoop {
                         frame.refresh();
                     }
                 }
-                if refresh {
-                    sink.send_event(::WindowEvent::Refresh, wid);
+                if redraw {
+                    redraws.push(wid);
                 }
                 if closed {
                     sink.send_event(::WindowEvent::Closed, wid);
                 }
             }
-        )
+        );
+        // process frame callbacks
+        let (frames, callbacks) = evq.state().get_mut(&self.store).frame_callbacks();
+        for (callback, frame_callbacks) in callbacks {
+            evq.register(&callback, frame_callback_impl(), frame_callbacks);
+        }
+        for wid in frames {
+            sink.send_event(::WindowEvent::FrameReady, wid);
+        }
+        // redraw requests come after the other events of the windows
+        for wid in redraws {
+            sink.send_event(::WindowEvent::RedrawRequested, wid);
+        }
+    }
+
+    /// Create a new surface for a window
+    pub fn create_surface(&self) -> wl_surface::WlSurface {
+        let mut guard = self.evq.borrow_mut();
+        let env = guard.state().get(&self.env_token).clone_inner().unwrap();
+        env.compositor.create_surface()
     }
 
-    /// Create a new window with given dimensions
+    /// Create the decorated frame of a window surface with given dimensions
     ///
     /// Grabs a lock on the event queue in the process
-    pub fn create_window<ID: 'static, F>(&self, width: u32, height: u32, implem: FrameImplementation<ID>, idata: F)
-        -> (wl_surface::WlSurface, Frame)
-    where F: FnOnce(&wl_surface::WlSurface) -> ID
+    pub fn create_frame<ID: 'static>(&self, surface: &wl_surface::WlSurface, width: u32, height: u32,
+                                     implem: FrameImplementation<ID>, idata: ID)
+        -> Result<Frame, WaylandError>
     {
-        let (surface, frame) = {
-            let mut guard = self.evq.borrow_mut();
-            let env = guard.state().get(&self.env_token).clone_inner().unwrap();
-            let shell = match guard.state().get(&self.ctxt_token).shell {
-                Some(Shell::Wl(ref wl_shell)) => Shell::Wl(wl_shell.clone().unwrap()),
-                Some(Shell::Xdg(ref xdg_shell)) => Shell::Xdg(xdg_shell.clone().unwrap()),
-                None => unreachable!()
-            };
-            let seat = guard.state().get(&self.ctxt_token).seat.as_ref().and_then(|s| s.clone());
-            let surface = env.compositor.create_surface();
-            let frame = create_frame(
-                &mut guard,
-                implem,
-                idata(&surface),
-                &surface, width as i32, height as i32,
-                &env.compositor,
-                &env.subcompositor,
-                &env.shm,
-                &shell,
-                seat
-            ).expect("Failed to create a tmpfile buffer.");
-            (surface, frame)
-        };
-
-        (surface, frame)
+        let mut guard = self.evq.borrow_mut();
+        make_frame(&mut guard, &self.env_token, &self.ctxt_token, surface, width, height, implem, idata)
     }
 }
 
+// Creates a frame for `surface`, which declares it as a shell surface to the compositor.
+fn make_frame<ID: 'static>(evq: &mut EventQueue, env_token: &StateToken<EnvHandler<InnerEnv>>,
+                           ctxt_token: &StateToken<StateContext>, surface: &wl_surface::WlSurface,
+                           width: u32, height: u32, implem: FrameImplementation<ID>, idata: ID)
+    -> Result<Frame, WaylandError>
+{
+    let env = evq.state().get(env_token).clone_inner().unwrap();
+    let shell = match evq.state().get(ctxt_token).shell {
+        Some(Shell::Wl(ref wl_shell)) => Shell::Wl(wl_shell.clone().unwrap()),
+        Some(Shell::Xdg(ref xdg_shell)) => Shell::Xdg(xdg_shell.clone().unwrap()),
+        None => unreachable!()
+    };
+    // the decorations are driven by the first seat
+    let seat = evq.state().get(ctxt_token).seats.first().and_then(|&(_, ref seat, _)| seat.clone());
+    create_frame(
+        evq,
+        implem,
+        idata,
+        surface, width as i32, height as i32,
+        &env.compositor,
+        &env.subcompositor,
+        &env.shm,
+        &shell,
+        seat
+    ).map_err(|()| WaylandError::Io("Failed to create a tmpfile buffer".to_owned()))
+}
+
 /*
  * Wayland protocol implementations
  */
@@ -416,13 +566,35 @@ fn env_notify() -> EnvNotify<StateToken<StateContext>> {
                 let xdg_shell = registry.bind::<zxdg_shell_v6::ZxdgShellV6>(1, id);
                 evqh.register(&xdg_shell, xdg_ping_implementation(), ());
                 evqh.state().get_mut(&token).shell = Some(Shell::Xdg(xdg_shell));
+            } else if interface == wl_seat::WlSeat::interface_name() {
+                // seats are only bound once the events loop is initialized
+                if evqh.state().get(&token).seat_shared.is_some() {
+                    bind_seat(evqh, &token, id, version);
+                }
             }
         },
         del_global: |evqh, token, _, id| {
+            let ctxt = evqh.state().get_mut(&token);
             // maybe this was a monitor, cleanup
-            evqh.state().get_mut(&token).monitors.retain(
-                |m| m.lock().unwrap().id != id
-            );
+            if let Some(index) = ctxt.monitors.iter().position(|m| m.lock().unwrap().id == id) {
+                let info = ctxt.monitors.remove(index);
+                let announced = info.lock().unwrap().done;
+                match ctxt.seat_shared {
+                    Some(ref shared) if announced => {
+                        let monitor = make_monitor_id(info);
+                        shared.sink.lock().unwrap().send_raw_event(::Event::MonitorRemoved(monitor));
+                    },
+                    _ => (),
+                }
+            }
+            // or a seat, whose devices are gone with it
+            if let Some(index) = ctxt.seats.iter().position(|&(name, _, _)| name == id) {
+                let (_, seat, idata) = ctxt.seats.remove(index);
+                idata.lock().unwrap().remove_devices();
+                if seat.version() >= 5 {
+                    seat.release();
+                }
+            }
         },
         ready: |_, _, _| {}
     }
@@ -436,62 +608,303 @@ fn xdg_ping_implementation() -> zxdg_shell_v6::Implementation<()> {
     }
 }
 
-struct SeatIData {
+// What the seats need to create their devices, shared by all of them.
+struct SeatShared {
     sink: Arc<Mutex<EventsLoopSink>>,
+    cursor_manager: Arc<Mutex<CursorManager>>,
+    relative_pointer_manager: Option<zwp_relative_pointer_manager_v1::ZwpRelativePointerManagerV1>,
+    tablet_manager: Option<zwp_tablet_manager_v2::ZwpTabletManagerV2>,
+    windows_token: StateToken<WindowStore>,
+    // the names of the seats seen so far, the id of a seat is the index of its name
+    seat_names: Mutex<Vec<String>>
+}
+
+impl SeatShared {
+    // A seat that is added again keeps its id, while a new seat never gets the id of a removed one,
+    // even if the compositor reuses the name of its global.
+    fn seat_id(&self, name: &str) -> u32 {
+        let mut names = self.seat_names.lock().unwrap();
+        match names.iter().position(|n| n == name) {
+            Some(index) => index as u32,
+            None => {
+                names.push(name.to_owned());
+                (names.len() - 1) as u32
+            }
+        }
+    }
+}
+
+fn bind_seat(evqh: &mut EventQueueHandle, token: &StateToken<StateContext>, id: u32, version: u32) {
+    use std::cmp::min;
+    let (seat, shared) = {
+        let ctxt = evqh.state().get(token);
+        let seat = ctxt.registry.bind::<wl_seat::WlSeat>(min(version, 5), id);
+        (seat, ctxt.seat_shared.clone().unwrap())
+    };
+    let idata = Arc::new(Mutex::new(SeatIData {
+        id: None,
+        name: String::new(),
+        capabilities: wl_seat::Capability::empty(),
+        shared: shared,
+        pointer: None,
+        relative_pointer: None,
+        keyboard: None,
+        touch: None,
+        tablet_seat: None,
+        tablets: Vec::new()
+    }));
+    evqh.register(&seat, seat_implementation(), idata.clone());
+    // the seats older than version 2 have no name, the name of their global stands for it
+    if seat.version() < 2 {
+        SeatIData::set_name(evqh, &idata, &seat, format!("seat{}", id));
+    }
+    evqh.state().get_mut(token).seats.push((id, seat, idata));
+}
+
+struct SeatIData {
+    // the id of the seat, known once it has a name
+    id: Option<u32>,
+    name: String,
+    // the capabilities of the seat, whose devices are created once it has an id
+    capabilities: wl_seat::Capability,
+    shared: Arc<SeatShared>,
     pointer: Option<wl_pointer::WlPointer>,
+    relative_pointer: Option<zwp_relative_pointer_v1::ZwpRelativePointerV1>,
     keyboard: Option<wl_keyboard::WlKeyboard>,
     touch: Option<wl_touch::WlTouch>,
-    windows_token: StateToken<WindowStore>
+    tablet_seat: Option<zwp_tablet_seat_v2::ZwpTabletSeatV2>,
+    tablets: Vec<zwp_tablet_v2::ZwpTabletV2>
 }
 
-fn seat_implementation() -> wl_seat::Implementation<SeatIData> {
-    wl_seat::Implementation {
-        name: |_, _, _, _| {},
-        capabilities: |evqh, idata, seat, capabilities| {
-            // create pointer if applicable
-            if capabilities.contains(wl_seat::Capability::Pointer) && idata.pointer.is_none() {
-                let pointer = seat.get_pointer().expect("Seat is not dead");
-                let p_idata = super::pointer::PointerIData::new(
-                    &idata.sink,
-                    idata.windows_token.clone()
-                );
-                evqh.register(&pointer, super::pointer::pointer_implementation(), p_idata);
-                idata.pointer = Some(pointer);
-            }
-            // destroy pointer if applicable
-            if !capabilities.contains(wl_seat::Capability::Pointer) {
-                if let Some(pointer) = idata.pointer.take() {
-                    pointer.release();
+impl SeatIData {
+    // Gives the seat its id the first time it is named, and creates its devices.
+    fn set_name(evqh: &mut EventQueueHandle, idata: &Arc<Mutex<SeatIData>>, seat: &wl_seat::WlSeat,
+                name: String)
+    {
+        let mut guard = idata.lock().unwrap();
+        guard.name = name;
+        if guard.id.is_some() {
+            return;
+        }
+        guard.id = Some(guard.shared.seat_id(&guard.name));
+        let capabilities = guard.capabilities;
+        guard.update_capabilities(evqh, seat, capabilities);
+        // the tablets are announced by a seat of their own
+        let tablet_seat = match guard.shared.tablet_manager {
+            Some(ref tablet_manager) => tablet_manager.get_tablet_seat(seat),
+            None => return,
+        };
+        if let RequestResult::Sent(tablet_seat) = tablet_seat {
+            evqh.register(&tablet_seat, tablet_seat_implementation(), idata.clone());
+            guard.tablet_seat = Some(tablet_seat);
+        }
+    }
+
+    // The devices are only created once the seat has an id.
+    fn device_id(&self, capability: Capability) -> ::DeviceId {
+        make_did(self.id.expect("Seat has no id"), capability)
+    }
+
+    fn send_device_event(&self, capability: Capability, event: ::DeviceEvent) {
+        self.shared.sink.lock().unwrap().send_raw_event(::Event::DeviceEvent {
+            device_id: self.device_id(capability),
+            event: event,
+        });
+    }
+
+    fn device_info(&self, capability: Capability) -> Option<::DeviceInfo> {
+        let (present, kind, suffix) = match capability {
+            Capability::Pointer => (self.pointer.is_some(), ::DeviceKind::Mouse, "pointer"),
+            Capability::Keyboard => (self.keyboard.is_some(), ::DeviceKind::Keyboard, "keyboard"),
+            Capability::Touch => (self.touch.is_some(), ::DeviceKind::Touchscreen, "touch"),
+            Capability::Tablet => (!self.tablets.is_empty(), ::DeviceKind::Tablet, "tablet"),
+        };
+        if !present {
+            return None;
+        }
+        // the compositor only tells which capabilities the seat has, not which devices provide them
+        Some(::DeviceInfo {
+            name: format!("{} {}", self.name, suffix),
+            kind: kind,
+            axes: Vec::new(),
+            buttons: 0,
+        })
+    }
+
+    fn update_capabilities(&mut self, evqh: &mut EventQueueHandle, seat: &wl_seat::WlSeat,
+                           capabilities: wl_seat::Capability)
+    {
+        // create pointer if applicable
+        if capabilities.contains(wl_seat::Capability::Pointer) && self.pointer.is_none() {
+            let pointer = seat.get_pointer().expect("Seat is not dead");
+            let device_id = self.device_id(Capability::Pointer);
+            let p_idata = super::pointer::PointerIData::new(
+                &self.shared.sink,
+                &self.shared.cursor_manager,
+                self.shared.windows_token.clone(),
+                device_id
+            );
+            evqh.register(&pointer, super::pointer::pointer_implementation(), p_idata);
+            self.shared.cursor_manager.lock().unwrap().add_pointer(&pointer);
+            // relative motions are reported as `DeviceEvent::MouseMotion`
+            if let Some(ref manager) = self.shared.relative_pointer_manager {
+                if let RequestResult::Sent(relative_pointer) = manager.get_relative_pointer(&pointer) {
+                    evqh.register(
+                        &relative_pointer,
+                        super::pointer::relative_pointer_implementation(),
+                        (self.shared.sink.clone(), device_id)
+                    );
+                    self.relative_pointer = Some(relative_pointer);
                 }
             }
-            // create keyboard if applicable
-            if capabilities.contains(wl_seat::Capability::Keyboard) && idata.keyboard.is_none() {
-                let kbd = seat.get_keyboard().expect("Seat is not dead");
-                init_keyboard(evqh, &kbd, &idata.sink);
-                idata.keyboard = Some(kbd);
+            self.pointer = Some(pointer);
+            self.send_device_event(Capability::Pointer, ::DeviceEvent::Added);
+        }
+        // destroy pointer if applicable
+        if !capabilities.contains(wl_seat::Capability::Pointer) {
+            self.remove_pointer();
+        }
+        // create keyboard if applicable
+        if capabilities.contains(wl_seat::Capability::Keyboard) && self.keyboard.is_none() {
+            let kbd = seat.get_keyboard().expect("Seat is not dead");
+            init_keyboard(evqh, &kbd, &self.shared.sink, self.device_id(Capability::Keyboard));
+            self.keyboard = Some(kbd);
+            self.send_device_event(Capability::Keyboard, ::DeviceEvent::Added);
+        }
+        // destroy keyboard if applicable
+        if !capabilities.contains(wl_seat::Capability::Keyboard) {
+            self.remove_keyboard();
+        }
+        // create touch if applicable
+        if capabilities.contains(wl_seat::Capability::Touch) && self.touch.is_none() {
+            let touch = seat.get_touch().expect("Seat is not dead");
+            let t_idata = super::touch::TouchIData::new(
+                &self.shared.sink,
+                self.shared.windows_token.clone(),
+                self.device_id(Capability::Touch)
+            );
+            evqh.register(&touch, super::touch::touch_implementation(), t_idata);
+            self.touch = Some(touch);
+            self.send_device_event(Capability::Touch, ::DeviceEvent::Added);
+        }
+        // destroy touch if applicable
+        if !capabilities.contains(wl_seat::Capability::Touch) {
+            self.remove_touch();
+        }
+    }
+
+    fn remove_pointer(&mut self) {
+        if let Some(relative_pointer) = self.relative_pointer.take() {
+            relative_pointer.destroy();
+        }
+        if let Some(pointer) = self.pointer.take() {
+            self.shared.cursor_manager.lock().unwrap().remove_pointer(&pointer);
+            // the seats older than version 3 can't release their devices
+            if pointer.version() >= 3 {
+                pointer.release();
+            }
+            self.send_device_event(Capability::Pointer, ::DeviceEvent::Removed);
+        }
+    }
+
+    fn remove_keyboard(&mut self) {
+        if let Some(kbd) = self.keyboard.take() {
+            if kbd.version() >= 3 {
+                kbd.release();
             }
-            // destroy keyboard if applicable
-            if !capabilities.contains(wl_seat::Capability::Keyboard) {
-                if let Some(kbd) = idata.keyboard.take() {
-                    kbd.release();
-                }
+            self.send_device_event(Capability::Keyboard, ::DeviceEvent::Removed);
+        }
+    }
+
+    fn remove_touch(&mut self) {
+        if let Some(touch) = self.touch.take() {
+            if touch.version() >= 3 {
+                touch.release();
             }
-            // create touch if applicable
-            if capabilities.contains(wl_seat::Capability::Touch) && idata.touch.is_none() {
-                let touch = seat.get_touch().expect("Seat is not dead");
-                let t_idata = super::touch::TouchIData::new(
-                    &idata.sink,
-                    idata.windows_token.clone()
-                );
-                evqh.register(&touch, super::touch::touch_implementation(), t_idata);
-                idata.touch = Some(touch);
+            self.send_device_event(Capability::Touch, ::DeviceEvent::Removed);
+        }
+    }
+
+    fn add_tablet(&mut self, tablet: zwp_tablet_v2::ZwpTabletV2) {
+        self.tablets.push(tablet);
+        // all the tablets of the seat are a single device
+        if self.tablets.len() == 1 {
+            self.send_device_event(Capability::Tablet, ::DeviceEvent::Added);
+        }
+    }
+
+    fn remove_tablet(&mut self, tablet: &zwp_tablet_v2::ZwpTabletV2) {
+        let len = self.tablets.len();
+        self.tablets.retain(|t| !t.equals(tablet));
+        if len != self.tablets.len() {
+            tablet.destroy();
+            if self.tablets.is_empty() {
+                self.send_device_event(Capability::Tablet, ::DeviceEvent::Removed);
             }
-            // destroy touch if applicable
-            if !capabilities.contains(wl_seat::Capability::Touch) {
-                if let Some(touch) = idata.touch.take() {
-                    touch.release();
-                }
+        }
+    }
+
+    fn remove_devices(&mut self) {
+        self.remove_pointer();
+        self.remove_keyboard();
+        self.remove_touch();
+        if !self.tablets.is_empty() {
+            for tablet in self.tablets.drain(..) {
+                tablet.destroy();
             }
+            self.send_device_event(Capability::Tablet, ::DeviceEvent::Removed);
+        }
+        if let Some(tablet_seat) = self.tablet_seat.take() {
+            tablet_seat.destroy();
+        }
+    }
+}
+
+fn seat_implementation() -> wl_seat::Implementation<Arc<Mutex<SeatIData>>> {
+    wl_seat::Implementation {
+        name: |evqh, idata, seat, name| {
+            SeatIData::set_name(evqh, idata, seat, name);
+        },
+        capabilities: |evqh, idata, seat, capabilities| {
+            let mut guard = idata.lock().unwrap();
+            guard.capabilities = capabilities;
+            if guard.id.is_some() {
+                guard.update_capabilities(evqh, seat, capabilities);
+            }
+        }
+    }
+}
+
+fn tablet_seat_implementation() -> zwp_tablet_seat_v2::Implementation<Arc<Mutex<SeatIData>>> {
+    zwp_tablet_seat_v2::Implementation {
+        tablet_added: |evqh, idata, _, tablet| {
+            evqh.register(&tablet, tablet_implementation(), idata.clone());
+            idata.lock().unwrap().add_tablet(tablet);
+        },
+        tool_added: |evqh, idata, _, tool| {
+            let idata = idata.lock().unwrap();
+            let t_idata = super::tablet::ToolIData::new(
+                &idata.shared.sink,
+                idata.shared.windows_token.clone(),
+                idata.device_id(Capability::Tablet)
+            );
+            evqh.register(&tool, super::tablet::tool_implementation(), t_idata);
+        },
+        pad_added: |_, _, _, pad| {
+            // the buttons, rings and strips of the pads are not supported
+            pad.destroy();
+        }
+    }
+}
+
+fn tablet_implementation() -> zwp_tablet_v2::Implementation<Arc<Mutex<SeatIData>>> {
+    zwp_tablet_v2::Implementation {
+        name: |_, _, _, _| {},
+        id: |_, _, _, _, _| {},
+        path: |_, _, _, _| {},
+        done: |_, _, _| {},
+        removed: |_, idata, tablet| {
+            idata.lock().unwrap().remove_tablet(tablet);
         }
     }
 }
@@ -502,30 +915,73 @@ fn seat_implementation() -> wl_seat::Implementation<SeatIData> {
 
 fn output_impl() -> wl_output::Implementation<StateToken<StateContext>> {
     wl_output::Implementation {
-        geometry: |evqh, token, output, x, y, _, _, _, make, model, _| {
+        geometry: |evqh, token, output, x, y, width, height, subpixel, make, model, transform| {
+            use self::wl_output::{Subpixel, Transform};
             let ctxt = evqh.state().get_mut(token);
             for info in &ctxt.monitors {
                 let mut guard = info.lock().unwrap();
                 if guard.output.equals(output) {
                     guard.pix_pos = (x, y);
                     guard.name = format!("{} - {}", make, model);
+                    guard.physical_size_mm = (width as u32, height as u32);
+                    guard.subpixel_layout = match subpixel {
+                        Subpixel::Unknown => SubpixelLayout::Unknown,
+                        Subpixel::None => SubpixelLayout::None,
+                        Subpixel::HorizontalRgb => SubpixelLayout::HorizontalRgb,
+                        Subpixel::HorizontalBgr => SubpixelLayout::HorizontalBgr,
+                        Subpixel::VerticalRgb => SubpixelLayout::VerticalRgb,
+                        Subpixel::VerticalBgr => SubpixelLayout::VerticalBgr,
+                    };
+                    // the flips are ignored
+                    guard.rotation = match transform {
+                        Transform::Normal | Transform::Flipped => MonitorRotation::Normal,
+                        Transform::_90 | Transform::Flipped90 => MonitorRotation::Rotate90,
+                        Transform::_180 | Transform::Flipped180 => MonitorRotation::Rotate180,
+                        Transform::_270 | Transform::Flipped270 => MonitorRotation::Rotate270,
+                    };
+                    guard.make = make;
+                    guard.model = model;
+                    return;
+                }
+            }
+        },
+        mode: |evqh, token, output, flags, w, h, refresh| {
+            let ctxt = evqh.state().get_mut(token);
+            for info in &ctxt.monitors {
+                let mut guard = info.lock().unwrap();
+                if guard.output.equals(output) {
+                    // the refresh rate is in mHz
+                    let mode = ((w as u32, h as u32), (refresh as f64 / 1000.0).round() as u16);
+                    if !guard.modes.contains(&mode) {
+                        guard.modes.push(mode);
+                    }
+                    if flags.contains(wl_output::Mode::Current) {
+                        guard.pix_size = mode.0;
+                        guard.refresh_rate = Some(mode.1);
+                    }
                     return;
                 }
             }
         },
-        mode: |evqh, token, output, flags, w, h, _refresh| {
-            if flags.contains(wl_output::Mode::Current) {
-                let ctxt = evqh.state().get_mut(token);
-                for info in &ctxt.monitors {
+        done: |evqh, token, output| {
+            let ctxt = evqh.state().get_mut(token);
+            for info in &ctxt.monitors {
+                let first = {
                     let mut guard = info.lock().unwrap();
-                    if guard.output.equals(output) {
-                        guard.pix_size = (w as u32, h as u32);
-                        return;
+                    if !guard.output.equals(output) {
+                        continue;
                     }
+                    !mem::replace(&mut guard.done, true)
+                };
+                // the outputs present when the events loop is created aren't announced
+                if let Some(ref shared) = ctxt.seat_shared {
+                    let monitor = make_monitor_id(info.clone());
+                    let event = if first { ::Event::MonitorAdded(monitor) } else { ::Event::MonitorChanged(monitor) };
+                    shared.sink.lock().unwrap().send_raw_event(event);
                 }
+                return;
             }
         },
-        done: |_, _, _| {},
         scale: |evqh, token, output, scale| {
             let ctxt = evqh.state().get_mut(token);
             for info in &ctxt.monitors {
@@ -545,7 +1001,18 @@ pub struct OutputInfo {
     pub scale: f32,
     pub pix_size: (u32, u32),
     pub pix_pos: (i32, i32),
-    pub name: String
+    pub name: String,
+    // the modes advertised by the output, as their dimensions and refresh rate
+    pub modes: Vec<((u32, u32), u16)>,
+    // the refresh rate of the current mode, if there is one
+    pub refresh_rate: Option<u16>,
+    pub physical_size_mm: (u32, u32),
+    pub subpixel_layout: SubpixelLayout,
+    pub rotation: MonitorRotation,
+    pub make: String,
+    pub model: String,
+    // whether the output sent its first `done` event, after which it is complete
+    pub done: bool,
 }
 
 impl OutputInfo {
@@ -556,7 +1023,15 @@ impl OutputInfo {
             scale: 1.0,
             pix_size: (0, 0),
             pix_pos: (0, 0),
-            name: "".into()
+            name: "".into(),
+            modes: Vec::new(),
+            refresh_rate: None,
+            physical_size_mm: (0, 0),
+            subpixel_layout: SubpixelLayout::Unknown,
+            rotation: MonitorRotation::Normal,
+            make: "".into(),
+            model: "".into(),
+            done: false,
         }
     }
 }
@@ -566,6 +1041,10 @@ pub struct MonitorId {
     pub info: Arc<Mutex<OutputInfo>>
 }
 
+fn make_monitor_id(info: Arc<Mutex<OutputInfo>>) -> RootMonitorId {
+    RootMonitorId { inner: PlatformMonitorId::Wayland(MonitorId { info: info }) }
+}
+
 impl MonitorId {
     pub fn get_name(&self) -> Option<String> {
         Some(self.info.lock().unwrap().name.clone())
@@ -588,4 +1067,61 @@ impl MonitorId {
     pub fn get_hidpi_factor(&self) -> f32 {
         self.info.lock().unwrap().scale
     }
+
+    pub fn get_physical_size_mm(&self) -> Option<(u32, u32)> {
+        match self.info.lock().unwrap().physical_size_mm {
+            (0, _) | (_, 0) => None,
+            size => Some(size),
+        }
+    }
+
+    pub fn get_refresh_rate(&self) -> Option<u16> {
+        // compositors that don't know the refresh rate of the output report 0
+        self.info.lock().unwrap().refresh_rate.and_then(|rate| if rate == 0 { None } else { Some(rate) })
+    }
+
+    pub fn get_make(&self) -> Option<String> {
+        let make = self.info.lock().unwrap().make.clone();
+        if make.is_empty() { None } else { Some(make) }
+    }
+
+    pub fn get_model(&self) -> Option<String> {
+        let model = self.info.lock().unwrap().model.clone();
+        if model.is_empty() { None } else { Some(model) }
+    }
+
+    #[inline]
+    pub fn get_subpixel_layout(&self) -> SubpixelLayout {
+        self.info.lock().unwrap().subpixel_layout
+    }
+
+    #[inline]
+    pub fn get_rotation(&self) -> MonitorRotation {
+        self.info.lock().unwrap().rotation
+    }
+
+    pub fn get_video_modes(&self) -> VecDeque<VideoMode> {
+        let modes = self.info.lock().unwrap().modes.clone();
+        modes.into_iter()
+            .map(|(dimensions, refresh_rate)| self.make_video_mode(dimensions, refresh_rate))
+            .collect()
+    }
+
+    pub fn get_current_video_mode(&self) -> Option<VideoMode> {
+        let (dimensions, refresh_rate) = {
+            let info = self.info.lock().unwrap();
+            (info.pix_size, info.refresh_rate)
+        };
+        refresh_rate.map(|refresh_rate| self.make_video_mode(dimensions, refresh_rate))
+    }
+
+    fn make_video_mode(&self, dimensions: (u32, u32), refresh_rate: u16) -> VideoMode {
+        VideoMode {
+            monitor: RootMonitorId { inner: PlatformMonitorId::Wayland(self.clone()) },
+            dimensions: dimensions,
+            refresh_rate: refresh_rate,
+            // wayland doesn't expose the bit depth of the outputs, assume the usual one
+            bit_depth: 24,
+        }
+    }
 }
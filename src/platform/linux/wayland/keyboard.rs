@@ -2,14 +2,16 @@ use std::sync::{Arc, Mutex};
 
 use {VirtualKeyCode, ElementState, WindowEvent as Event, KeyboardInput, ModifiersState};
 
-use super::{EventsLoopSink, WindowId, make_wid, DeviceId};
+use super::{EventsLoopSink, WindowId, make_wid};
 use super::wayland_kbd::{MappedKeyboardImplementation, register_kbd};
 use wayland_client::protocol::wl_keyboard;
 use wayland_client::EventQueueHandle;
 
-pub fn init_keyboard(evq: &mut EventQueueHandle, keyboard: &wl_keyboard::WlKeyboard, sink: &Arc<Mutex<EventsLoopSink>>) {
+pub fn init_keyboard(evq: &mut EventQueueHandle, keyboard: &wl_keyboard::WlKeyboard, sink: &Arc<Mutex<EventsLoopSink>>,
+                     device_id: ::DeviceId) {
     let idata = KeyboardIData {
         sink: sink.clone(),
+        device_id: device_id,
         target: None
     };
 
@@ -18,6 +20,7 @@ pub fn init_keyboard(evq: &mut EventQueueHandle, keyboard: &wl_keyboard::WlKeybo
         // fallback implementation
         let idata = KeyboardIData {
             sink: sink.clone(),
+            device_id: device_id,
             target: None
         };
         evq.register(keyboard, raw_keyboard_impl(), idata);
@@ -26,6 +29,7 @@ pub fn init_keyboard(evq: &mut EventQueueHandle, keyboard: &wl_keyboard::WlKeybo
 
 struct KeyboardIData {
     sink: Arc<Mutex<EventsLoopSink>>,
+    device_id: ::DeviceId,
     target: Option<WindowId>
 }
 
@@ -51,7 +55,7 @@ fn mapped_keyboard_impl() -> MappedKeyboardImplementation<KeyboardIData> {
                 let mut guard = idata.sink.lock().unwrap();
                 guard.send_event(
                     Event::KeyboardInput {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         input: KeyboardInput {
                             state: state,
                             scancode: rawkey,
@@ -108,7 +112,7 @@ fn raw_keyboard_impl() -> wl_keyboard::Implementation<KeyboardIData> {
                 };
                 idata.sink.lock().unwrap().send_event(
                     Event::KeyboardInput {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         input: KeyboardInput {
                             state: state,
                             scancode: key,
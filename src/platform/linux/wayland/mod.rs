@@ -7,17 +7,91 @@ extern crate wayland_kbd;
 extern crate wayland_window;
 extern crate wayland_protocols;
 
-use wayland_client::protocol::wl_surface;
-use wayland_client::Proxy;
+use std::error::Error;
+use std::fmt;
+use std::io;
 
+use wayland_client::protocol::{wl_display, wl_surface};
+use wayland_client::{FatalError, Proxy};
+
+mod cursor;
 mod event_loop;
 mod pointer;
+mod tablet;
 mod touch;
 mod keyboard;
 mod window;
 
+/// Error triggered by the wayland connection.
+#[derive(Debug, Clone)]
+pub enum WaylandError {
+    /// Communicating with the compositor failed.
+    Io(String),
+    /// The compositor sent a protocol error. The connection is no longer usable.
+    Protocol {
+        /// Name of the interface of the object that generated the error.
+        interface: String,
+        /// Id of the object that generated the error.
+        proxy_id: u32,
+        /// Code of the error, as defined by the interface.
+        error_code: u32,
+    },
+}
+
+impl WaylandError {
+    /// Builds an error from a failed I/O operation on the connection, retrieving the protocol
+    /// error that caused it if there is one.
+    pub fn from_io(display: &wl_display::WlDisplay, err: io::Error) -> WaylandError {
+        match display.last_error() {
+            Some(FatalError::Protocol { interface, proxy_id, error_code }) => WaylandError::Protocol {
+                interface: interface,
+                proxy_id: proxy_id,
+                error_code: error_code,
+            },
+            Some(FatalError::Io(err)) => WaylandError::Io(format!("{}", err)),
+            None => WaylandError::Io(format!("{}", err)),
+        }
+    }
+}
+
+impl Error for WaylandError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            WaylandError::Io(ref text) => text,
+            WaylandError::Protocol { .. } => "The wayland compositor sent a protocol error",
+        }
+    }
+}
+
+impl fmt::Display for WaylandError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            WaylandError::Io(ref text) => write!(formatter, "Wayland I/O error: {}", text),
+            WaylandError::Protocol { ref interface, proxy_id, error_code } => {
+                write!(formatter, "Wayland protocol error on {}@{} (code: {})",
+                       interface, proxy_id, error_code)
+            },
+        }
+    }
+}
+
+/// An input device: one of the capabilities of a seat.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct DeviceId;
+pub struct DeviceId {
+    // the name of the seat, as an index in the names of the seats seen so far
+    seat: u32,
+    capability: Capability,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Capability {
+    Pointer,
+    Keyboard,
+    Touch,
+    // the tools of the tablets of the seat
+    Tablet,
+}
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct WindowId(usize);
@@ -25,4 +99,9 @@ pub struct WindowId(usize);
 #[inline]
 fn make_wid(s: &wl_surface::WlSurface) -> WindowId {
     WindowId(s.ptr() as usize)
+}
+
+#[inline]
+fn make_did(seat: u32, capability: Capability) -> ::DeviceId {
+    ::DeviceId(::platform::DeviceId::Wayland(DeviceId { seat: seat, capability: capability }))
 }
\ No newline at end of file
@@ -1,16 +1,21 @@
 use std::sync::{Arc, Mutex};
 
-use {WindowEvent as Event, ElementState, MouseButton, MouseScrollDelta, TouchPhase};
+use {WindowEvent as Event, DeviceEvent, ElementState, MouseButton, MouseScrollDelta, TouchPhase};
 
-use super::{WindowId, DeviceId};
+use super::WindowId;
+use super::cursor::CursorManager;
 use super::event_loop::EventsLoopSink;
 use super::window::WindowStore;
 
 use wayland_client::{Proxy, StateToken};
 use wayland_client::protocol::wl_pointer;
 
+use super::wayland_protocols::unstable::relative_pointer::v1::client::zwp_relative_pointer_v1;
+
 pub struct PointerIData {
     sink: Arc<Mutex<EventsLoopSink>>,
+    device_id: ::DeviceId,
+    cursor_manager: Arc<Mutex<CursorManager>>,
     windows_token: StateToken<WindowStore>,
     mouse_focus: Option<WindowId>,
     axis_buffer: Option<(f32, f32)>,
@@ -19,11 +24,14 @@ pub struct PointerIData {
 }
 
 impl PointerIData {
-    pub fn new(sink: &Arc<Mutex<EventsLoopSink>>, token: StateToken<WindowStore>)
+    pub fn new(sink: &Arc<Mutex<EventsLoopSink>>, cursor_manager: &Arc<Mutex<CursorManager>>,
+               token: StateToken<WindowStore>, device_id: ::DeviceId)
         -> PointerIData
     {
         PointerIData {
             sink: sink.clone(),
+            device_id: device_id,
+            cursor_manager: cursor_manager.clone(),
             windows_token: token,
             mouse_focus: None,
             axis_buffer: None,
@@ -35,34 +43,36 @@ impl PointerIData {
 
 pub fn pointer_implementation() -> wl_pointer::Implementation<PointerIData> {
     wl_pointer::Implementation {
-        enter: |evqh, idata, _, _, surface, x, y| {
-            let wid = evqh.state().get(&idata.windows_token).find_wid(surface);
-            if let Some(wid) = wid {
+        enter: |evqh, idata, pointer, serial, surface, x, y| {
+            let store = evqh.state().get(&idata.windows_token);
+            if let Some((wid, cursor, scale)) = store.find_cursor(surface) {
                 idata.mouse_focus = Some(wid);
+                idata.cursor_manager.lock().unwrap().enter(pointer, wid, serial, cursor, scale);
                 let mut guard = idata.sink.lock().unwrap();
                 guard.send_event(
                     Event::CursorEntered {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                     },
                     wid,
                 );
                 guard.send_event(
                     Event::CursorMoved {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         position: (x, y),
                     },
                     wid,
                 );
             }
         },
-        leave: |evqh, idata, _, _, surface| {
+        leave: |evqh, idata, pointer, _, surface| {
             idata.mouse_focus = None;
+            idata.cursor_manager.lock().unwrap().leave(pointer);
             let wid = evqh.state().get(&idata.windows_token).find_wid(surface);
             if let Some(wid) = wid {
                 let mut guard = idata.sink.lock().unwrap();
                 guard.send_event(
                     Event::CursorLeft {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                     },
                     wid,
                 );
@@ -72,7 +82,7 @@ pub fn pointer_implementation() -> wl_pointer::Implementation<PointerIData> {
             if let Some(wid) = idata.mouse_focus {
                 idata.sink.lock().unwrap().send_event(
                     Event::CursorMoved {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         position: (x, y)
                     },
                     wid
@@ -94,7 +104,7 @@ pub fn pointer_implementation() -> wl_pointer::Implementation<PointerIData> {
                 };
                 idata.sink.lock().unwrap().send_event(
                     Event::MouseInput {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         state: state,
                         button: button,
                     },
@@ -114,7 +124,7 @@ pub fn pointer_implementation() -> wl_pointer::Implementation<PointerIData> {
                     }
                     idata.sink.lock().unwrap().send_event(
                         Event::MouseWheel {
-                            device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                            device_id: idata.device_id,
                             delta: MouseScrollDelta::PixelDelta(x as f32, y as f32),
                             phase: TouchPhase::Moved,
                         },
@@ -142,7 +152,7 @@ pub fn pointer_implementation() -> wl_pointer::Implementation<PointerIData> {
                 if let Some((x, y)) = axis_discrete_buffer {
                     idata.sink.lock().unwrap().send_event(
                         Event::MouseWheel {
-                            device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                            device_id: idata.device_id,
                             delta: MouseScrollDelta::LineDelta(x as f32, y as f32),
                             phase: idata.axis_state,
                         },
@@ -151,7 +161,7 @@ pub fn pointer_implementation() -> wl_pointer::Implementation<PointerIData> {
                 } else if let Some((x, y)) = axis_buffer {
                     idata.sink.lock().unwrap().send_event(
                         Event::MouseWheel {
-                            device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                            device_id: idata.device_id,
                             delta: MouseScrollDelta::PixelDelta(x as f32, y as f32),
                             phase: idata.axis_state,
                         },
@@ -179,3 +189,19 @@ pub fn pointer_implementation() -> wl_pointer::Implementation<PointerIData> {
         },
     }
 }
+
+pub fn relative_pointer_implementation()
+    -> zwp_relative_pointer_v1::Implementation<(Arc<Mutex<EventsLoopSink>>, ::DeviceId)>
+{
+    zwp_relative_pointer_v1::Implementation {
+        relative_motion: |_, &mut (ref sink, device_id), _, _, _, _, _, dx_unaccel, dy_unaccel| {
+            // the motion is not accelerated, like the raw motion on X11
+            sink.lock().unwrap().send_raw_event(::Event::DeviceEvent {
+                device_id: device_id,
+                event: DeviceEvent::MouseMotion {
+                    delta: (dx_unaccel, dy_unaccel),
+                },
+            });
+        },
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::{Arc, Mutex};
+
+use {WindowEvent as Event, Pen, PenPhase, PenTool};
+
+use super::WindowId;
+use super::event_loop::EventsLoopSink;
+use super::window::WindowStore;
+
+use wayland_client::StateToken;
+
+use super::wayland_protocols::unstable::tablet::v2::client::zwp_tablet_tool_v2;
+
+// The buttons on the barrel of the tools, as evdev codes
+const BTN_STYLUS: u32 = 0x14b;
+const BTN_STYLUS2: u32 = 0x14c;
+const BTN_STYLUS3: u32 = 0x149;
+
+pub struct ToolIData {
+    sink: Arc<Mutex<EventsLoopSink>>,
+    device_id: ::DeviceId,
+    windows_token: StateToken<WindowStore>,
+    tool: PenTool,
+    focus: Option<WindowId>,
+    // the state is accumulated until the `frame` event
+    phase: Option<PenPhase>,
+    location: (f64, f64),
+    down: bool,
+    barrel_buttons: u32,
+    pressure: Option<f64>,
+    tilt: Option<(f64, f64)>,
+    rotation: Option<f64>,
+    distance: Option<f64>,
+}
+
+impl ToolIData {
+    pub fn new(sink: &Arc<Mutex<EventsLoopSink>>, token: StateToken<WindowStore>, device_id: ::DeviceId)
+        -> ToolIData
+    {
+        ToolIData {
+            sink: sink.clone(),
+            device_id: device_id,
+            windows_token: token,
+            tool: PenTool::Pen,
+            focus: None,
+            phase: None,
+            location: (0.0, 0.0),
+            down: false,
+            barrel_buttons: 0,
+            pressure: None,
+            tilt: None,
+            rotation: None,
+            distance: None,
+        }
+    }
+
+    fn moved(&mut self) {
+        if self.phase.is_none() {
+            self.phase = Some(PenPhase::Moved);
+        }
+    }
+}
+
+pub fn tool_implementation() -> zwp_tablet_tool_v2::Implementation<ToolIData> {
+    zwp_tablet_tool_v2::Implementation {
+        type_: |_, idata, _, tool_type| {
+            use self::zwp_tablet_tool_v2::Type;
+            idata.tool = match tool_type {
+                Type::Pen => PenTool::Pen,
+                Type::Eraser => PenTool::Eraser,
+                Type::Brush => PenTool::Brush,
+                Type::Pencil => PenTool::Pencil,
+                Type::Airbrush => PenTool::Airbrush,
+                Type::Finger => PenTool::Finger,
+                Type::Mouse => PenTool::Mouse,
+                Type::Lens => PenTool::Lens,
+            };
+        },
+        hardware_serial: |_, _, _, _, _| {},
+        hardware_id_wacom: |_, _, _, _, _| {},
+        capability: |_, idata, _, capability| {
+            use self::zwp_tablet_tool_v2::Capability;
+            // the axes of the tool are `None` unless it has them
+            match capability {
+                Capability::Tilt => idata.tilt = Some((0.0, 0.0)),
+                Capability::Pressure => idata.pressure = Some(0.0),
+                Capability::Distance => idata.distance = Some(0.0),
+                Capability::Rotation => idata.rotation = Some(0.0),
+                Capability::Slider | Capability::Wheel => (),
+            }
+        },
+        done: |_, _, _| {},
+        removed: |_, _, tool| {
+            tool.destroy();
+        },
+        proximity_in: |evqh, idata, _, _serial, _tablet, surface| {
+            let wid = evqh.state().get(&idata.windows_token).find_wid(surface);
+            idata.focus = wid;
+            idata.phase = Some(PenPhase::Entered);
+        },
+        proximity_out: |_, idata, _| {
+            idata.phase = Some(PenPhase::Left);
+        },
+        down: |_, idata, _, _serial| {
+            idata.down = true;
+            idata.moved();
+        },
+        up: |_, idata, _| {
+            idata.down = false;
+            idata.moved();
+        },
+        motion: |_, idata, _, x, y| {
+            idata.location = (x, y);
+            idata.moved();
+        },
+        pressure: |_, idata, _, pressure| {
+            idata.pressure = Some(pressure as f64 / 65535.0);
+            idata.moved();
+        },
+        distance: |_, idata, _, distance| {
+            idata.distance = Some(distance as f64 / 65535.0);
+            idata.moved();
+        },
+        tilt: |_, idata, _, tilt_x, tilt_y| {
+            idata.tilt = Some((tilt_x, tilt_y));
+            idata.moved();
+        },
+        rotation: |_, idata, _, degrees| {
+            idata.rotation = Some(degrees);
+            idata.moved();
+        },
+        slider: |_, _, _, _| {},
+        wheel: |_, _, _, _, _| {},
+        button: |_, idata, _, _serial, button, state| {
+            let bit = match button {
+                BTN_STYLUS => 1,
+                BTN_STYLUS2 => 1 << 1,
+                BTN_STYLUS3 => 1 << 2,
+                _ => return
+            };
+            match state {
+                zwp_tablet_tool_v2::ButtonState::Pressed => idata.barrel_buttons |= bit,
+                zwp_tablet_tool_v2::ButtonState::Released => idata.barrel_buttons &= !bit,
+            }
+            idata.moved();
+        },
+        frame: |_, idata, _, _time| {
+            let phase = match idata.phase.take() {
+                Some(phase) => phase,
+                None => return
+            };
+            if let Some(wid) = idata.focus {
+                idata.sink.lock().unwrap().send_event(
+                    Event::Pen(Pen {
+                        device_id: idata.device_id,
+                        phase: phase,
+                        tool: idata.tool,
+                        location: idata.location,
+                        down: idata.down,
+                        barrel_buttons: idata.barrel_buttons,
+                        pressure: idata.pressure,
+                        tilt: idata.tilt,
+                        rotation: idata.rotation,
+                        distance: idata.distance,
+                    }),
+                    wid
+                );
+            }
+            if phase == PenPhase::Left {
+                idata.focus = None;
+            }
+        },
+    }
+}
@@ -2,7 +2,7 @@ use std::sync::{Arc, Mutex};
 
 use {WindowEvent as Event, TouchPhase};
 
-use super::{WindowId, DeviceId};
+use super::WindowId;
 use super::event_loop::EventsLoopSink;
 use super::window::WindowStore;
 
@@ -11,6 +11,7 @@ use wayland_client::protocol::wl_touch;
 
 pub struct TouchIData {
     sink: Arc<Mutex<EventsLoopSink>>,
+    device_id: ::DeviceId,
     windows_token: StateToken<WindowStore>,
     pending_ids: Vec<TouchPoint>,
 }
@@ -22,11 +23,12 @@ struct TouchPoint {
 }
 
 impl TouchIData {
-    pub fn new(sink: &Arc<Mutex<EventsLoopSink>>, token: StateToken<WindowStore>)
+    pub fn new(sink: &Arc<Mutex<EventsLoopSink>>, token: StateToken<WindowStore>, device_id: ::DeviceId)
         -> TouchIData
     {
         TouchIData {
             sink: sink.clone(),
+            device_id: device_id,
             windows_token: token,
             pending_ids: Vec::new(),
         }
@@ -41,10 +43,13 @@ pub fn touch_implementation() -> wl_touch::Implementation<TouchIData> {
                 let mut guard = idata.sink.lock().unwrap();
                 guard.send_event(
                     Event::Touch(::Touch {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         phase: TouchPhase::Started,
                         location: (x, y),
-                        id: touch_id as u64
+                        id: touch_id as u64,
+                        force: None,
+                        radius: None,
+                        orientation: None,
                     }),
                     wid,
                 );
@@ -62,10 +67,13 @@ pub fn touch_implementation() -> wl_touch::Implementation<TouchIData> {
                 let mut guard = idata.sink.lock().unwrap();
                 guard.send_event(
                     Event::Touch(::Touch {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         phase: TouchPhase::Ended,
                         location: pt.location,
-                        id: touch_id as u64
+                        id: touch_id as u64,
+                        force: None,
+                        radius: None,
+                        orientation: None,
                     }),
                     pt.wid,
                 );
@@ -78,10 +86,13 @@ pub fn touch_implementation() -> wl_touch::Implementation<TouchIData> {
                 pt.location = (x, y);
                 guard.send_event(
                     Event::Touch(::Touch {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         phase: TouchPhase::Moved,
                         location: (x, y),
-                        id: touch_id as u64
+                        id: touch_id as u64,
+                        force: None,
+                        radius: None,
+                        orientation: None,
                     }),
                     pt.wid,
                 );
@@ -93,10 +104,13 @@ pub fn touch_implementation() -> wl_touch::Implementation<TouchIData> {
             for pt in idata.pending_ids.drain(..) {
                 guard.send_event(
                     Event::Touch(::Touch {
-                        device_id: ::DeviceId(::platform::DeviceId::Wayland(DeviceId)),
+                        device_id: idata.device_id,
                         phase: TouchPhase::Cancelled,
                         location: pt.location,
-                        id: pt.id as u64
+                        id: pt.id as u64,
+                        force: None,
+                        radius: None,
+                        orientation: None,
                     }),
                     pt.wid,
                 );
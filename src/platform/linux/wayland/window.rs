@@ -1,23 +1,31 @@
 use std::sync::{Arc, Mutex, Weak};
 
-use wayland_client::protocol::{wl_display,wl_surface};
-use wayland_client::{Proxy, StateToken};
+use wayland_client::protocol::{wl_callback, wl_display, wl_output, wl_surface};
+use wayland_client::{Proxy, RequestResult, StateToken};
 
-use {CreationError, MouseCursor, CursorState, WindowAttributes};
+use {CreationError, Fullscreen, MouseCursor, CursorState, WindowAttributes};
 use platform::MonitorId as PlatformMonitorId;
 use window::MonitorId as RootMonitorId;
 
-use super::{EventsLoop, WindowId, make_wid, MonitorId};
-use super::wayland_window::{Frame, FrameImplementation, State as FrameState};
+use super::{EventsLoop, WindowId, WaylandError, make_wid, MonitorId};
+use super::wayland_window::{Configure, Frame, FrameImplementation, State as FrameState};
+use super::wayland_protocols::unstable::xdg_shell::v6::client::zxdg_toplevel_v6::State as XdgState;
 use super::event_loop::StateContext;
+use super::cursor::CursorManager;
 
 pub struct Window {
     surface: wl_surface::WlSurface,
-    frame: Arc<Mutex<Frame>>,
+    frame: Arc<Mutex<Option<Frame>>>,
+    frame_attributes: Arc<Mutex<FrameAttributes>>,
     monitors: Arc<Mutex<MonitorList>>,
     size: Arc<Mutex<(u32, u32)>>,
+    cursor: Arc<Mutex<MouseCursor>>,
+    cursor_state: Arc<Mutex<CursorState>>,
+    cursor_manager: Arc<Mutex<CursorManager>>,
     kill_switch: (Arc<Mutex<bool>>, Arc<Mutex<bool>>),
     display: Arc<wl_display::WlDisplay>,
+    redraw_requested: Arc<Mutex<bool>>,
+    frame_callbacks: Arc<Mutex<FrameCallbacks>>,
 }
 
 impl Window {
@@ -25,30 +33,47 @@ impl Window {
     {
         let (width, height) = attributes.dimensions.unwrap_or((800,600));
 
-        // Create the decorated surface
-        let size = Arc::new(Mutex::new((width, height)));
-        let store_token = evlp.store.clone();
-        let (surface, mut frame) = evlp.create_window(
-            width, height, decorated_impl(),
-            |surface| FrameIData {
-                surface: surface.clone().unwrap(),
-                store_token: store_token.clone()
-            }
-        );
-        // Check for fullscreen requirements
-        if let Some(RootMonitorId { inner: PlatformMonitorId::Wayland(ref monitor_id) }) = attributes.fullscreen {
-            let info = monitor_id.info.lock().unwrap();
-            frame.set_state(FrameState::Fullscreen(Some(&info.output)));
-        } else if attributes.maximized {
-            frame.set_state(FrameState::Maximized);
+        // an X11 monitor can't be used to make a wayland window fullscreen
+        match attributes.fullscreen.as_ref().map(Fullscreen::get_monitor) {
+            Some(&RootMonitorId { inner: PlatformMonitorId::Wayland(_) }) | None => (),
+            _ => return Err(CreationError::UnsupportedAttribute { name: "fullscreen" }),
         }
 
-        // set decorations
-        frame.set_decorate(attributes.decorations);
-
-        // min-max dimensions
-        frame.set_min_size(attributes.min_dimensions.map(|(w, h)| (w as i32, h as i32)));
-        frame.set_max_size(attributes.max_dimensions.map(|(w, h)| (w as i32, h as i32)));
+        let size = Arc::new(Mutex::new((width, height)));
+        let store_token = evlp.store.clone();
+        let frame_attributes = FrameAttributes {
+            visible: attributes.visible,
+            title: attributes.title.clone(),
+            decorate: attributes.decorations,
+            min_size: attributes.min_dimensions.map(|(w, h)| (w as i32, h as i32)),
+            max_size: attributes.max_dimensions.map(|(w, h)| (w as i32, h as i32)),
+            maximized: attributes.maximized,
+            fullscreen: attributes.fullscreen.is_some(),
+            // the video mode of an exclusive fullscreen is left to the compositor
+            fullscreen_output: match attributes.fullscreen.as_ref().map(Fullscreen::get_monitor) {
+                Some(&RootMonitorId { inner: PlatformMonitorId::Wayland(ref monitor_id) }) => {
+                    monitor_id.info.lock().unwrap().output.clone()
+                },
+                _ => None
+            },
+        };
+
+        // Create the surface, and its decorations if the window is visible. An hidden window has
+        // no frame, so it is not mapped until it is shown.
+        let surface = evlp.create_surface();
+        let frame = if attributes.visible {
+            let mut frame = evlp.create_frame(
+                &surface, width, height, decorated_impl(),
+                FrameIData {
+                    surface: surface.clone().unwrap(),
+                    store_token: store_token.clone()
+                }
+            ).map_err(CreationError::Wayland)?;
+            frame_attributes.apply(&mut frame);
+            Some(frame)
+        } else {
+            None
+        };
 
         // setup the monitor tracking
         let monitor_list = Arc::new(Mutex::new(MonitorList::default()));
@@ -59,7 +84,12 @@ impl Window {
         }
 
         let kill_switch = Arc::new(Mutex::new(false));
+        let cursor = Arc::new(Mutex::new(MouseCursor::Default));
+        let cursor_state = Arc::new(Mutex::new(CursorState::Normal));
+        let redraw_requested = Arc::new(Mutex::new(false));
+        let frame_callbacks = Arc::new(Mutex::new(FrameCallbacks::default()));
         let frame = Arc::new(Mutex::new(frame));
+        let frame_attributes = Arc::new(Mutex::new(frame_attributes));
 
         {
             let mut evq = evlp.evq.borrow_mut();
@@ -69,19 +99,33 @@ impl Window {
                 need_refresh: false,
                 need_frame_refresh: true,
                 surface: surface.clone().unwrap(),
+                size: size.clone(),
+                monitors: monitor_list.clone(),
+                cursor: cursor.clone(),
+                cursor_state: cursor_state.clone(),
                 kill_switch: kill_switch.clone(),
-                frame: Arc::downgrade(&frame)
+                redraw_requested: redraw_requested.clone(),
+                frame_callbacks: frame_callbacks.clone(),
+                frame: Arc::downgrade(&frame),
+                frame_attributes: frame_attributes.clone(),
             });
-            evq.sync_roundtrip().unwrap();
+            evq.sync_roundtrip()
+                .map_err(|err| CreationError::Wayland(WaylandError::from_io(&evlp.display, err)))?;
         }
 
         Ok(Window {
             display: evlp.display.clone(),
             surface: surface,
             frame: frame,
+            frame_attributes: frame_attributes,
             monitors: monitor_list,
             size: size,
-            kill_switch: (kill_switch, evlp.cleanup_needed.clone())
+            cursor: cursor,
+            cursor_state: cursor_state,
+            cursor_manager: evlp.cursor_manager.clone(),
+            kill_switch: (kill_switch, evlp.cleanup_needed.clone()),
+            redraw_requested: redraw_requested,
+            frame_callbacks: frame_callbacks,
         })
     }
 
@@ -91,17 +135,94 @@ impl Window {
     }
 
     pub fn set_title(&self, title: &str) {
-        self.frame.lock().unwrap().set_title(title.into());
+        self.frame_attributes.lock().unwrap().title = title.into();
+        if let Some(ref frame) = *self.frame.lock().unwrap() {
+            frame.set_title(title.into());
+        }
+    }
+
+    pub fn request_redraw(&self) {
+        let mut redraw_requested = self.redraw_requested.lock().unwrap();
+        if !*redraw_requested {
+            *redraw_requested = true;
+            // Cause the `EventsLoop` to break from `dispatch` if it is currently blocked.
+            self.display.sync();
+            let _ = self.display.flush();
+        }
+    }
+
+    pub fn set_frame_callbacks(&self, enabled: bool) {
+        let mut frame_callbacks = self.frame_callbacks.lock().unwrap();
+        if enabled == frame_callbacks.enabled {
+            return;
+        }
+        frame_callbacks.enabled = enabled;
+        // The first frame is ready right away: a frame callback only fires after a commit, so the
+        // user must draw once before the compositor can pace the following frames. A callback that
+        // is still pending from before they were disabled makes the frame ready when it fires.
+        frame_callbacks.ready = enabled && !frame_callbacks.pending;
+        if enabled {
+            // Cause the `EventsLoop` to break from `dispatch` so that the callback is requested.
+            self.display.sync();
+            let _ = self.display.flush();
+        }
+    }
+
+    #[inline]
+    pub fn get_frame_callbacks(&self) -> bool {
+        self.frame_callbacks.lock().unwrap().enabled
     }
 
     #[inline]
     pub fn show(&self) {
-        // TODO
+        self.set_visible(true);
     }
 
     #[inline]
     pub fn hide(&self) {
-        // TODO
+        self.set_visible(false);
+    }
+
+    // The window is mapped or unmapped by the `EventsLoop`, which can create its frame.
+    fn set_visible(&self, visible: bool) {
+        let mut frame_attributes = self.frame_attributes.lock().unwrap();
+        if frame_attributes.visible != visible {
+            frame_attributes.visible = visible;
+            // Cause the `EventsLoop` to break from `dispatch` if it is currently blocked.
+            self.display.sync();
+            let _ = self.display.flush();
+        }
+    }
+
+    pub fn set_maximized(&self, maximized: bool) {
+        let mut frame_attributes = self.frame_attributes.lock().unwrap();
+        frame_attributes.maximized = maximized;
+        self.apply_state(&frame_attributes);
+    }
+
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        let mut frame_attributes = self.frame_attributes.lock().unwrap();
+        match fullscreen.as_ref().map(Fullscreen::get_monitor) {
+            Some(&RootMonitorId { inner: PlatformMonitorId::Wayland(ref monitor_id) }) => {
+                frame_attributes.fullscreen = true;
+                frame_attributes.fullscreen_output = monitor_id.info.lock().unwrap().output.clone();
+            },
+            // an X11 monitor can't be used to make a wayland window fullscreen
+            Some(_) => return,
+            None => {
+                frame_attributes.fullscreen = false;
+                frame_attributes.fullscreen_output = None;
+            },
+        }
+        self.apply_state(&frame_attributes);
+    }
+
+    // A hidden window gets its state when its frame is created.
+    fn apply_state(&self, frame_attributes: &FrameAttributes) {
+        if let Some(ref mut frame) = *self.frame.lock().unwrap() {
+            frame_attributes.apply_state(frame);
+            let _ = self.display.flush();
+        }
     }
 
     #[inline]
@@ -129,35 +250,55 @@ impl Window {
     #[inline]
     // NOTE: This will only resize the borders, the contents must be updated by the user
     pub fn set_inner_size(&self, x: u32, y: u32) {
-        self.frame.lock().unwrap().resize(x as i32, y as i32);
+        if let Some(ref mut frame) = *self.frame.lock().unwrap() {
+            frame.resize(x as i32, y as i32);
+        }
         *(self.size.lock().unwrap()) = (x, y);
     }
 
-    #[inline]
-    pub fn set_cursor(&self, _cursor: MouseCursor) {
-        // TODO
+    pub fn set_cursor(&self, cursor: MouseCursor) {
+        *self.cursor.lock().unwrap() = cursor;
+        if cursor_hidden(*self.cursor_state.lock().unwrap()) {
+            // the cursor will be shown when the state changes
+            return;
+        }
+        let scale = self.hidpi_factor() as i32;
+        self.cursor_manager.lock().unwrap().set_cursor(self.id(), cursor, scale);
+        let _ = self.display.flush();
     }
 
-    #[inline]
     pub fn set_cursor_state(&self, state: CursorState) -> Result<(), String> {
-        use CursorState::{Grab, Normal, Hide};
-        // TODO : not yet possible on wayland to grab cursor
+        let scale = self.hidpi_factor() as i32;
+        let mut cursor_state = self.cursor_state.lock().unwrap();
+        if *cursor_state == state {
+            return Ok(());
+        }
+
+        let mut cursor_manager = self.cursor_manager.lock().unwrap();
+        if cursor_grabbed(*cursor_state) {
+            cursor_manager.ungrab(&self.surface);
+        }
         match state {
-            Grab => Err("Cursor cannot be grabbed on wayland yet.".to_string()),
-            Hide => Err("Cursor cannot be hidden on wayland yet.".to_string()),
-            Normal => Ok(())
+            CursorState::Grab | CursorState::Lock => cursor_manager.grab(&self.surface, true)?,
+            CursorState::Confine => cursor_manager.grab(&self.surface, false)?,
+            CursorState::Normal | CursorState::Hide => (),
         }
+        *cursor_state = state;
+
+        // a hidden cursor is a null cursor surface
+        let cursor = if cursor_hidden(state) {
+            MouseCursor::NoneCursor
+        } else {
+            *self.cursor.lock().unwrap()
+        };
+        cursor_manager.set_cursor(self.id(), cursor, scale);
+        let _ = self.display.flush();
+        Ok(())
     }
 
     #[inline]
     pub fn hidpi_factor(&self) -> f32 {
-        let mut factor = 1.0;
-        let guard = self.monitors.lock().unwrap();
-        for monitor_id in &guard.monitors {
-            let info = monitor_id.info.lock().unwrap();
-            if info.scale > factor { factor = info.scale; }
-        }
-        factor
+        self.monitors.lock().unwrap().hidpi_factor()
     }
 
     #[inline]
@@ -184,23 +325,121 @@ impl Window {
 
 impl Drop for Window {
     fn drop(&mut self) {
+        if cursor_grabbed(*self.cursor_state.lock().unwrap()) {
+            self.cursor_manager.lock().unwrap().ungrab(&self.surface);
+        }
         *(self.kill_switch.0.lock().unwrap()) = true;
         *(self.kill_switch.1.lock().unwrap()) = true;
     }
 }
 
+fn cursor_hidden(state: CursorState) -> bool {
+    state == CursorState::Hide || state == CursorState::Lock
+}
+
+fn cursor_grabbed(state: CursorState) -> bool {
+    match state {
+        CursorState::Grab | CursorState::Confine | CursorState::Lock => true,
+        CursorState::Normal | CursorState::Hide => false,
+    }
+}
+
 /*
  * Internal store for windows
  */
 
 struct InternalWindow {
     surface: wl_surface::WlSurface,
+    size: Arc<Mutex<(u32, u32)>>,
+    monitors: Arc<Mutex<MonitorList>>,
+    cursor: Arc<Mutex<MouseCursor>>,
+    cursor_state: Arc<Mutex<CursorState>>,
     newsize: Option<(i32, i32)>,
     need_refresh: bool,
     need_frame_refresh: bool,
     closed: bool,
     kill_switch: Arc<Mutex<bool>>,
-    frame: Weak<Mutex<Frame>>
+    redraw_requested: Arc<Mutex<bool>>,
+    frame_callbacks: Arc<Mutex<FrameCallbacks>>,
+    frame: Weak<Mutex<Option<Frame>>>,
+    frame_attributes: Arc<Mutex<FrameAttributes>>,
+}
+
+// The attributes of the frame of a window, which are applied again when the frame is created
+// after the window was hidden.
+//
+// The maximized and fullscreen states are updated with the ones the compositor grants, if the
+// shell reports them.
+struct FrameAttributes {
+    visible: bool,
+    title: String,
+    decorate: bool,
+    min_size: Option<(i32, i32)>,
+    max_size: Option<(i32, i32)>,
+    maximized: bool,
+    fullscreen: bool,
+    // the output chosen by the user, the compositor picks one otherwise
+    fullscreen_output: Option<wl_output::WlOutput>,
+}
+
+impl FrameAttributes {
+    fn apply(&self, frame: &mut Frame) {
+        frame.set_title(self.title.clone());
+        self.apply_state(frame);
+        frame.set_decorate(self.decorate);
+        frame.set_min_size(self.min_size);
+        frame.set_max_size(self.max_size);
+    }
+
+    fn apply_state(&self, frame: &mut Frame) {
+        if self.fullscreen {
+            frame.set_state(FrameState::Fullscreen(self.fullscreen_output.as_ref()));
+        } else if self.maximized {
+            frame.set_state(FrameState::Maximized);
+        } else {
+            frame.set_state(FrameState::Regular);
+        }
+    }
+}
+
+/// A hidden window that was shown, and whose frame must be created to map it again.
+pub struct PendingMap {
+    pub surface: wl_surface::WlSurface,
+    pub size: (u32, u32),
+    frame: Arc<Mutex<Option<Frame>>>,
+    frame_attributes: Arc<Mutex<FrameAttributes>>,
+    store_token: StateToken<WindowStore>,
+}
+
+impl PendingMap {
+    pub fn frame_implementation(&self) -> (FrameImplementation<FrameIData>, FrameIData) {
+        let idata = FrameIData {
+            surface: self.surface.clone().unwrap(),
+            store_token: self.store_token.clone()
+        };
+        (decorated_impl(), idata)
+    }
+
+    pub fn set_frame(self, mut frame: Frame) {
+        self.frame_attributes.lock().unwrap().apply(&mut frame);
+        *self.frame.lock().unwrap() = Some(frame);
+    }
+
+    /// Gives up on mapping the window when its frame can't be created, rather than retrying on
+    /// every dispatch. The window stays hidden until it is shown again.
+    pub fn fail(self, err: WaylandError) {
+        eprintln!("[winit] Failed to map a window: {}", err);
+        self.frame_attributes.lock().unwrap().visible = false;
+    }
+}
+
+#[derive(Default)]
+pub struct FrameCallbacks {
+    enabled: bool,
+    // a `wl_callback` has been requested and is not done yet
+    pending: bool,
+    // the compositor signaled that a new frame can be drawn
+    ready: bool,
 }
 
 pub struct WindowStore {
@@ -221,6 +460,22 @@ impl WindowStore {
         None
     }
 
+    /// Returns the id of the window of `surface`, with its cursor and the scale to draw it.
+    pub fn find_cursor(&self, surface: &wl_surface::WlSurface) -> Option<(WindowId, MouseCursor, i32)> {
+        for window in &self.windows {
+            if surface.equals(&window.surface) {
+                let cursor = if cursor_hidden(*window.cursor_state.lock().unwrap()) {
+                    MouseCursor::NoneCursor
+                } else {
+                    *window.cursor.lock().unwrap()
+                };
+                let scale = window.monitors.lock().unwrap().hidpi_factor() as i32;
+                return Some((make_wid(surface), cursor, scale));
+            }
+        }
+        None
+    }
+
     pub fn cleanup(&mut self) {
         self.windows.retain(|w| {
             if *w.kill_switch.lock().unwrap() {
@@ -233,19 +488,91 @@ impl WindowStore {
         });
     }
 
+    /// Returns the windows that are ready to draw a new frame, and requests the next frame
+    /// callback of the windows that have none pending.
+    ///
+    /// The returned callbacks must be registered with `frame_callback_impl`.
+    pub fn frame_callbacks(&mut self)
+        -> (Vec<WindowId>, Vec<(wl_callback::WlCallback, Arc<Mutex<FrameCallbacks>>)>)
+    {
+        let mut ready = Vec::new();
+        let mut callbacks = Vec::new();
+        for window in &self.windows {
+            let mut frame_callbacks = window.frame_callbacks.lock().unwrap();
+            if !frame_callbacks.enabled {
+                frame_callbacks.ready = false;
+                continue;
+            }
+            if frame_callbacks.ready {
+                frame_callbacks.ready = false;
+                ready.push(make_wid(&window.surface));
+            }
+            if !frame_callbacks.pending {
+                // the callback will be attached to the next commit of the surface
+                if let RequestResult::Sent(callback) = window.surface.frame() {
+                    frame_callbacks.pending = true;
+                    callbacks.push((callback, window.frame_callbacks.clone()));
+                }
+            }
+        }
+        (ready, callbacks)
+    }
+
+    /// Unmaps the windows that were hidden, and returns the windows that were shown and must be
+    /// mapped again.
+    pub fn update_visibility(&mut self, token: &StateToken<WindowStore>) -> Vec<PendingMap> {
+        let mut to_map = Vec::new();
+        for window in &mut self.windows {
+            let frame = match window.frame.upgrade() {
+                Some(frame) => frame,
+                None => continue
+            };
+            let visible = window.frame_attributes.lock().unwrap().visible;
+            let mapped = frame.lock().unwrap().is_some();
+            if mapped && !visible {
+                // detach the contents, then destroy the shell surface along with the decorations
+                window.surface.attach(None, 0, 0);
+                window.surface.commit();
+                *frame.lock().unwrap() = None;
+            } else if !mapped && visible {
+                // the contents must be drawn again once the window is mapped
+                window.need_refresh = true;
+                window.need_frame_refresh = true;
+                to_map.push(PendingMap {
+                    surface: window.surface.clone().unwrap(),
+                    size: *window.size.lock().unwrap(),
+                    frame: frame.clone(),
+                    frame_attributes: window.frame_attributes.clone(),
+                    store_token: token.clone(),
+                });
+            }
+        }
+        to_map
+    }
+
     pub fn for_each<F>(&mut self, mut f: F)
     where F: FnMut(Option<(i32, i32)>, bool, bool, bool, WindowId, Option<&mut Frame>)
     {
         for window in &mut self.windows {
             let opt_arc = window.frame.upgrade();
             let mut opt_mutex_lock = opt_arc.as_ref().map(|m| m.lock().unwrap());
+            if let Some((w, h)) = window.newsize {
+                *window.size.lock().unwrap() = (w as u32, h as u32);
+            }
+            // a redraw requested by the user is merged with the one caused by a refresh
+            let redraw = {
+                let mut redraw_requested = window.redraw_requested.lock().unwrap();
+                let redraw = *redraw_requested || window.need_refresh;
+                *redraw_requested = false;
+                redraw
+            };
             f(
                 window.newsize.take(),
-                window.need_refresh,
                 window.need_frame_refresh,
+                redraw,
                 window.closed,
                 make_wid(&window.surface),
-                opt_mutex_lock.as_mut().map(|m| &mut **m)
+                opt_mutex_lock.as_mut().and_then(|m| m.as_mut())
             );
             window.need_refresh = false;
             // avoid re-spamming the event
@@ -258,17 +585,26 @@ impl WindowStore {
  * Protocol implementation
  */
 
-struct FrameIData {
+pub struct FrameIData {
     store_token: StateToken<WindowStore>,
     surface: wl_surface::WlSurface
 }
 
 fn decorated_impl() -> FrameImplementation<FrameIData> {
     FrameImplementation {
-        configure: |evqh, idata, _, newsize| {
+        configure: |evqh, idata, cfg, newsize| {
             let store = evqh.state().get_mut(&idata.store_token);
             for window in &mut store.windows {
                 if window.surface.equals(&idata.surface) {
+                    // track the state the compositor granted, wl_shell doesn't tell it
+                    if let Configure::Xdg(ref states) = cfg {
+                        let mut frame_attributes = window.frame_attributes.lock().unwrap();
+                        frame_attributes.maximized = states.contains(&XdgState::Maximized);
+                        frame_attributes.fullscreen = states.contains(&XdgState::Fullscreen);
+                        if !frame_attributes.fullscreen {
+                            frame_attributes.fullscreen_output = None;
+                        }
+                    }
                     window.newsize = newsize;
                     window.need_refresh = true;
                     window.need_frame_refresh = true;
@@ -297,11 +633,33 @@ fn decorated_impl() -> FrameImplementation<FrameIData> {
     }
 }
 
+pub fn frame_callback_impl() -> wl_callback::Implementation<Arc<Mutex<FrameCallbacks>>> {
+    wl_callback::Implementation {
+        done: |_, frame_callbacks, _, _| {
+            let mut frame_callbacks = frame_callbacks.lock().unwrap();
+            frame_callbacks.pending = false;
+            frame_callbacks.ready = true;
+        }
+    }
+}
+
 #[derive(Default)]
 struct MonitorList {
     monitors: Vec<MonitorId>
 }
 
+impl MonitorList {
+    // the highest scale factor of the monitors the window is on
+    fn hidpi_factor(&self) -> f32 {
+        let mut factor = 1.0;
+        for monitor_id in &self.monitors {
+            let info = monitor_id.info.lock().unwrap();
+            if info.scale > factor { factor = info.scale; }
+        }
+        factor
+    }
+}
+
 fn surface_impl() -> wl_surface::Implementation<(StateToken<StateContext>, Arc<Mutex<MonitorList>>)> {
     wl_surface::Implementation {
         enter: |evqh, &mut (ref token, ref list), _, output| {
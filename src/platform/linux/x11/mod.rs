@@ -7,14 +7,16 @@ pub use self::xdisplay::{XConnection, XNotSupported, XError};
 pub mod ffi;
 
 use platform::PlatformSpecificWindowBuilderAttributes;
-use {CreationError, Event, EventsLoopClosed, WindowEvent, DeviceEvent,
-     KeyboardInput, ControlFlow};
+use {CreationError, Event, EventsLoopClosed, EventsLoopCreationError, WindowEvent, DeviceEvent,
+     KeyboardInput, ControlFlow, CursorState, Pen, PenPhase, PenTool, TouchPhase};
 
 use std::{mem, ptr, slice};
 use std::sync::{Arc, Mutex, Weak};
 use std::sync::atomic::{self, AtomicBool};
 use std::collections::HashMap;
 use std::ffi::CStr;
+use std::os::unix::io::RawFd;
+use std::time::Duration;
 
 use libc::{self, c_uchar, c_char, c_int};
 
@@ -30,12 +32,21 @@ mod xdisplay;
 // As this module is nested into platform/linux, its code is not _exactly_ the same as
 // the one generated by the macro.
 
+// The `QueuedAlready` mode of `XEventsQueued`, which counts the events without reading any.
+const QUEUED_ALREADY: c_int = 0;
+
 pub struct EventsLoop {
     display: Arc<XConnection>,
     wm_delete_window: ffi::Atom,
     windows: Arc<Mutex<HashMap<WindowId, WindowData>>>,
     devices: Mutex<HashMap<DeviceId, Device>>,
     xi2ext: XExtension,
+    // The first event code of XRandR, if the extension is available
+    randr_event_base: Option<c_int>,
+    // The monitors, to report the changes of the screen configuration
+    monitors: Mutex<Vec<MonitorId>>,
+    // The screen configuration changed during the current batch of events
+    monitors_changed: AtomicBool,
     pending_wakeup: Arc<AtomicBool>,
     root: ffi::Window,
     // A dummy, `InputOnly` window that we can use to receive wakeup events and interrupt blocking
@@ -51,9 +62,9 @@ pub struct EventsLoopProxy {
 }
 
 impl EventsLoop {
-    pub fn new(display: Arc<XConnection>) -> EventsLoop {
+    pub fn new(display: Arc<XConnection>) -> Result<EventsLoop, EventsLoopCreationError> {
         let wm_delete_window = unsafe { (display.xlib.XInternAtom)(display.display, b"WM_DELETE_WINDOW\0".as_ptr() as *const c_char, 0) };
-        display.check_errors().expect("Failed to call XInternAtom");
+        try!(display.check_errors().map_err(|err| EventsLoopCreationError::OsError(format!("Failed to call XInternAtom: {}", err))));
 
         let xi2ext = unsafe {
             let mut result = XExtension {
@@ -68,7 +79,7 @@ impl EventsLoop {
                 &mut result.first_event_id as *mut c_int,
                 &mut result.first_error_id as *mut c_int);
             if res == ffi::False {
-                panic!("X server missing XInput extension");
+                return Err(EventsLoopCreationError::MissingExtension("XInputExtension"));
             }
             result
         };
@@ -78,18 +89,34 @@ impl EventsLoop {
             let mut xinput_minor_ver = ffi::XI_2_Minor;
 
             if (display.xinput2.XIQueryVersion)(display.display, &mut xinput_major_ver, &mut xinput_minor_ver) != ffi::Success as libc::c_int {
-                panic!("X server has XInput extension {}.{} but does not support XInput2", xinput_major_ver, xinput_minor_ver);
+                // The X server has the XInput extension, but in a version older than XInput2
+                return Err(EventsLoopCreationError::MissingExtension("XInput2"));
             }
         }
 
         let root = unsafe { (display.xlib.XDefaultRootWindow)(display.display) };
 
+        let randr_event_base = unsafe {
+            let mut event_base = 0;
+            let mut error_base = 0;
+            if (display.xrandr.XRRQueryExtension)(display.display, &mut event_base, &mut error_base) == ffi::True {
+                // Register for monitor hotplug and configuration events
+                (display.xrandr.XRRSelectInput)(display.display, root,
+                    ffi::RRScreenChangeNotifyMask | ffi::RRCrtcChangeNotifyMask | ffi::RROutputChangeNotifyMask);
+                Some(event_base)
+            } else {
+                None
+            }
+        };
+        let monitors = if randr_event_base.is_some() { get_available_monitors(&display) } else { Vec::new() };
+
         let wakeup_dummy_window = unsafe {
             let (x, y, w, h) = (10, 10, 10, 10);
             let (border_w, border_px, background_px) = (0, 0, 0);
             (display.xlib.XCreateSimpleWindow)(display.display, root, x, y, w, h,
                                                border_w, border_px, background_px)
         };
+        try!(display.check_errors().map_err(|err| EventsLoopCreationError::OsError(format!("Failed to create the wakeup window: {}", err))));
 
         let result = EventsLoop {
             pending_wakeup: Arc::new(AtomicBool::new(false)),
@@ -98,6 +125,9 @@ impl EventsLoop {
             windows: Arc::new(Mutex::new(HashMap::new())),
             devices: Mutex::new(HashMap::new()),
             xi2ext: xi2ext,
+            randr_event_base: randr_event_base,
+            monitors: Mutex::new(monitors),
+            monitors_changed: AtomicBool::new(false),
             root: root,
             wakeup_dummy_window: wakeup_dummy_window,
         };
@@ -118,7 +148,7 @@ impl EventsLoop {
             result.init_device(ffi::XIAllDevices);
         }
 
-        result
+        Ok(result)
     }
 
     /// Returns the `XConnection` of this events loop.
@@ -137,6 +167,38 @@ impl EventsLoop {
 
     pub fn poll_events<F>(&mut self, mut callback: F)
         where F: FnMut(Event)
+    {
+        callback(Event::NewEvents);
+        self.process_pending_events(&mut callback);
+        self.update_monitors(&mut callback);
+        self.process_redraw_requests(&mut callback);
+        callback(Event::EventsCleared);
+    }
+
+    // Emits a single `RedrawRequested` for each window that needs to be redrawn.
+    fn process_redraw_requests<F>(&self, mut callback: F)
+        where F: FnMut(Event)
+    {
+        // Gymnastics to ensure self.windows isn't locked when we invoke callback
+        let requested: Vec<WindowId> = self.windows.lock().unwrap().iter_mut()
+            .filter(|&(_, ref window_data)| window_data.redraw_requested)
+            .map(|(&window_id, window_data)| {
+                window_data.redraw_requested = false;
+                window_id
+            })
+            .collect();
+
+        for window_id in requested {
+            callback(Event::WindowEvent {
+                window_id: ::WindowId(::platform::WindowId::X(window_id)),
+                event: WindowEvent::RedrawRequested,
+            });
+        }
+    }
+
+    // Processes the events that can be retrieved without blocking.
+    fn process_pending_events<F>(&self, mut callback: F)
+        where F: FnMut(Event)
     {
         let xlib = &self.display.xlib;
 
@@ -156,11 +218,48 @@ impl EventsLoop {
         }
     }
 
+    /// Processes the available events, then flushes the requests made by the callback so that
+    /// the caller can wait on the connection's file descriptor.
+    pub fn dispatch_pending<F>(&mut self, mut callback: F)
+        where F: FnMut(Event)
+    {
+        loop {
+            self.poll_events(&mut callback);
+            unsafe { (self.display.xlib.XFlush)(self.display.display) };
+            // The round trips made by the callback once `XPending` reported nothing may have read
+            // events into the queue of Xlib, which the fd doesn't report anymore.
+            let queued = unsafe { (self.display.xlib.XEventsQueued)(self.display.display, QUEUED_ALREADY) };
+            if queued == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Returns the file descriptor of the connection to the X server.
+    #[inline]
+    pub fn get_fd(&self) -> RawFd {
+        unsafe { (self.display.xlib.XConnectionNumber)(self.display.display) }
+    }
+
+    pub fn wait_events_timeout<F>(&mut self, timeout: Duration, callback: F)
+        where F: FnMut(Event)
+    {
+        unsafe {
+            // `XPending` flushes the output buffer and reads what is available on the connection,
+            // so if it reports nothing the only way to get an event is to wait for the socket.
+            if (self.display.xlib.XPending)(self.display.display) == 0 {
+                super::wait_for_fd(self.get_fd(), timeout);
+            }
+        }
+
+        self.poll_events(callback);
+    }
+
     pub fn run_forever<F>(&mut self, mut callback: F)
         where F: FnMut(Event) -> ControlFlow
     {
-        self.pending_wakeup.store(false, atomic::Ordering::Relaxed);
-
+        // The wakeups that happened before are kept, `run_forever` is entered again whenever the
+        // `EventsLoop` stops waiting for a timer.
         let xlib = &self.display.xlib;
 
         let mut xev = unsafe { mem::uninitialized() };
@@ -170,7 +269,7 @@ impl EventsLoop {
 
             let mut control_flow = ControlFlow::Continue;
 
-            // Track whether or not `Break` was returned when processing the event.
+            // Track whether or not `Break` was returned when processing the events.
             {
                 let mut cb = |event| {
                     if let ControlFlow::Break = callback(event) {
@@ -178,7 +277,13 @@ impl EventsLoop {
                     }
                 };
 
+                cb(Event::NewEvents);
                 self.process_event(&mut xev, &mut cb);
+                // Process the rest of the batch before reporting that the queue is empty.
+                self.process_pending_events(&mut cb);
+                self.update_monitors(&mut cb);
+                self.process_redraw_requests(&mut cb);
+                cb(Event::EventsCleared);
             }
 
             if let ControlFlow::Break = control_flow {
@@ -202,7 +307,9 @@ impl EventsLoop {
         match xev.get_type() {
             ffi::MappingNotify => {
                 unsafe { (xlib.XRefreshKeyboardMapping)(xev.as_mut()); }
-                self.display.check_errors().expect("Failed to call XRefreshKeyboardMapping");
+                if let Err(err) = self.display.check_errors() {
+                    eprintln!("[winit] Failed to call XRefreshKeyboardMapping: {}", err);
+                }
             }
 
             ffi::ClientMessage => {
@@ -226,7 +333,7 @@ impl EventsLoop {
                 let (resized, moved) = {
                     let mut windows = self.windows.lock().unwrap();
                     let window_data = windows.get_mut(&WindowId(xwindow)).unwrap();
-                    if window_data.config.is_none() {
+                    let (resized, moved) = if window_data.config.is_none() {
                         window_data.config = Some(WindowConfig::new(xev));
                         (true, true)
                     } else {
@@ -239,7 +346,11 @@ impl EventsLoop {
                             window.position = position;
                             true
                         } else { false })
+                    };
+                    if resized {
+                        window_data.redraw_requested = true;
                     }
+                    (resized, moved)
                 };
                 if resized {
                     callback(Event::WindowEvent { window_id: wid, event: WindowEvent::Resized(xev.width as u32, xev.height as u32) });
@@ -250,7 +361,9 @@ impl EventsLoop {
             }
 
             ffi::Expose => {
-                callback(Event::WindowEvent { window_id: wid, event: WindowEvent::Refresh });
+                if let Some(window_data) = self.windows.lock().unwrap().get_mut(&WindowId(xwindow)) {
+                    window_data.redraw_requested = true;
+                }
             }
 
             // FIXME: Use XInput2 + libxkbcommon for keyboard input!
@@ -346,7 +459,7 @@ impl EventsLoop {
                 use events::ElementState::{Pressed, Released};
                 use events::MouseButton::{Left, Right, Middle, Other};
                 use events::MouseScrollDelta::LineDelta;
-                use events::{Touch, TouchPhase};
+                use events::Touch;
 
                 match xev.evtype {
                     ffi::XI_ButtonPress | ffi::XI_ButtonRelease => {
@@ -362,6 +475,16 @@ impl EventsLoop {
                         } else {
                             Released
                         };
+                        let pen = self.devices.lock().unwrap().get_mut(&DeviceId(xev.sourceid))
+                            .and_then(|device| device.pen.as_mut())
+                            .map(|pen| {
+                                pen.update(&xev.valuators);
+                                pen.pen(mkdid(xev.sourceid), PenPhase::Moved, (xev.event_x, xev.event_y),
+                                        &xev.buttons, Some((xev.detail, state == Pressed)))
+                            });
+                        if let Some(pen) = pen {
+                            callback(Event::WindowEvent { window_id: wid, event: WindowEvent::Pen(pen) });
+                        }
                         match xev.detail as u32 {
                             ffi::Button1 => callback(Event::WindowEvent { window_id: wid, event:
                                                                           MouseInput { device_id: did, state: state, button: Left } }),
@@ -400,7 +523,22 @@ impl EventsLoop {
                         if {
                             let mut windows = self.windows.lock().unwrap();
                             let window_data = windows.get_mut(&WindowId(xev.event)).unwrap();
-                            if Some(new_cursor_pos) != window_data.cursor_pos {
+                            if window_data.cursor_locked {
+                                // A locked cursor is warped back to the center of the window, its
+                                // motion is only reported by `DeviceEvent::MouseMotion`.
+                                if let Some(ref config) = window_data.config {
+                                    let center = (config.size.0 / 2, config.size.1 / 2);
+                                    if new_cursor_pos != (center.0 as f64, center.1 as f64) {
+                                        unsafe {
+                                            (self.display.xlib.XWarpPointer)(
+                                                self.display.display, 0, xev.event, 0, 0, 0, 0,
+                                                center.0, center.1
+                                            );
+                                        }
+                                    }
+                                }
+                                false
+                            } else if Some(new_cursor_pos) != window_data.cursor_pos {
                                 window_data.cursor_pos = Some(new_cursor_pos);
                                 true
                             } else { false }
@@ -444,6 +582,12 @@ impl EventsLoop {
                                     value = unsafe { value.offset(1) };
                                 }
                             }
+
+                            if let Some(ref mut pen) = physical_device.pen {
+                                pen.update(&xev.valuators);
+                                let pen = pen.pen(mkdid(xev.sourceid), PenPhase::Moved, new_cursor_pos, &xev.buttons, None);
+                                events.push(Event::WindowEvent { window_id: wid, event: WindowEvent::Pen(pen) });
+                            }
                         }
                         for event in events {
                             callback(event);
@@ -466,18 +610,34 @@ impl EventsLoop {
                         callback(Event::WindowEvent { window_id: wid, event: CursorMoved {
                             device_id: mkdid(xev.deviceid),
                             position: new_cursor_pos
-                        }})
+                        }});
+
+                        if let Some(ref pen) = physical_device.pen {
+                            let pen = pen.pen(mkdid(xev.sourceid), PenPhase::Entered, new_cursor_pos, &xev.buttons, None);
+                            callback(Event::WindowEvent { window_id: wid, event: WindowEvent::Pen(pen) });
+                        }
                     }
                     ffi::XI_Leave => {
                         let xev: &ffi::XILeaveEvent = unsafe { &*(xev.data as *const _) };
-                        callback(Event::WindowEvent { window_id: mkwid(xev.event), event: CursorLeft { device_id: mkdid(xev.deviceid) } })
+                        callback(Event::WindowEvent { window_id: mkwid(xev.event), event: CursorLeft { device_id: mkdid(xev.deviceid) } });
+
+                        let pen = self.devices.lock().unwrap().get(&DeviceId(xev.sourceid))
+                            .and_then(|device| device.pen)
+                            .map(|pen| pen.pen(mkdid(xev.sourceid), PenPhase::Left, (xev.event_x, xev.event_y), &xev.buttons, None));
+                        if let Some(pen) = pen {
+                            callback(Event::WindowEvent { window_id: mkwid(xev.event), event: WindowEvent::Pen(pen) });
+                        }
                     }
                     ffi::XI_FocusIn => {
                         let xev: &ffi::XIFocusInEvent = unsafe { &*(xev.data as *const _) };
-                        unsafe {
+                        let window = unsafe {
                             let mut windows = self.windows.lock().unwrap();
                             let window_data = windows.get_mut(&WindowId(xev.event)).unwrap();
                             (self.display.xlib.XSetICFocus)(window_data.ic);
+                            window_data.window.upgrade()
+                        };
+                        if let Some(window) = window {
+                            window.update_video_mode(true);
                         }
                         callback(Event::WindowEvent { window_id: mkwid(xev.event), event: Focused(true) });
 
@@ -489,10 +649,14 @@ impl EventsLoop {
                     }
                     ffi::XI_FocusOut => {
                         let xev: &ffi::XIFocusOutEvent = unsafe { &*(xev.data as *const _) };
-                        unsafe {
+                        let window = unsafe {
                             let mut windows = self.windows.lock().unwrap();
                             let window_data = windows.get_mut(&WindowId(xev.event)).unwrap();
                             (self.display.xlib.XUnsetICFocus)(window_data.ic);
+                            window_data.window.upgrade()
+                        };
+                        if let Some(window) = window {
+                            window.update_video_mode(false);
                         }
                         callback(Event::WindowEvent { window_id: mkwid(xev.event), event: Focused(false) })
                     }
@@ -500,20 +664,55 @@ impl EventsLoop {
                     ffi::XI_TouchBegin | ffi::XI_TouchUpdate | ffi::XI_TouchEnd => {
                         let xev: &ffi::XIDeviceEvent = unsafe { &*(xev.data as *const _) };
                         let wid = mkwid(xev.event);
-                        let phase = match xev.evtype {
-                            ffi::XI_TouchBegin => TouchPhase::Started,
-                            ffi::XI_TouchUpdate => TouchPhase::Moved,
-                            ffi::XI_TouchEnd => TouchPhase::Ended,
-                            _ => unreachable!()
+                        let phase = {
+                            let mut windows = self.windows.lock().unwrap();
+                            let window_data = match windows.get_mut(&WindowId(xev.event)) {
+                                Some(window_data) => window_data,
+                                None => return
+                            };
+                            let id = xev.detail as u32;
+                            match xev.evtype {
+                                ffi::XI_TouchBegin => {
+                                    // the ownership is signaled right after, if the window has it
+                                    window_data.touches.insert(id, false);
+                                    TouchPhase::Started
+                                },
+                                ffi::XI_TouchUpdate if window_data.touches.contains_key(&id) => TouchPhase::Moved,
+                                ffi::XI_TouchEnd => match window_data.touches.remove(&id) {
+                                    Some(true) => TouchPhase::Ended,
+                                    // the touch sequence was accepted by another client
+                                    Some(false) => TouchPhase::Cancelled,
+                                    None => return
+                                },
+                                _ => return
+                            }
                         };
+                        let (force, radius, orientation) = self.devices.lock().unwrap()
+                            .get_mut(&DeviceId(xev.sourceid))
+                            .and_then(|device| device.touch.as_mut())
+                            .map(|touch| touch.update(xev.detail, phase, &xev.valuators))
+                            .unwrap_or((None, None, None));
                         callback(Event::WindowEvent { window_id: wid, event: WindowEvent::Touch(Touch {
                             device_id: mkdid(xev.deviceid),
                             phase: phase,
                             location: (xev.event_x, xev.event_y),
                             id: xev.detail as u64,
+                            force: force,
+                            radius: radius,
+                            orientation: orientation,
                         })})
                     }
 
+                    ffi::XI_TouchOwnership => {
+                        let xev: &ffi::XITouchOwnershipEvent = unsafe { &*(xev.data as *const _) };
+                        let mut windows = self.windows.lock().unwrap();
+                        if let Some(window_data) = windows.get_mut(&WindowId(xev.event)) {
+                            if let Some(owned) = window_data.touches.get_mut(&xev.touchid) {
+                                *owned = true;
+                            }
+                        }
+                    }
+
                     ffi::XI_RawButtonPress | ffi::XI_RawButtonRelease => {
                         let xev: &ffi::XIRawEvent = unsafe { &*(xev.data as *const _) };
                         if xev.flags & ffi::XIPointerEmulated == 0 {
@@ -601,10 +800,59 @@ impl EventsLoop {
                 }
             }
 
+            event_type if self.is_randr_event(event_type) => {
+                unsafe { (self.display.xrandr.XRRUpdateConfiguration)(xev); }
+                self.monitors_changed.store(true, atomic::Ordering::Relaxed);
+            }
+
             _ => {}
         }
     }
 
+    fn is_randr_event(&self, event_type: c_int) -> bool {
+        match self.randr_event_base {
+            Some(base) => event_type == base + ffi::RRScreenChangeNotify || event_type == base + ffi::RRNotify,
+            None => false,
+        }
+    }
+
+    // Reports the monitors that were added, removed or changed since the last change of the screen
+    // configuration. A change usually comes with several events, so the monitors are queried once,
+    // at the end of the batch of events.
+    fn update_monitors<F>(&self, callback: &mut F)
+        where F: FnMut(Event)
+    {
+        if !self.monitors_changed.swap(false, atomic::Ordering::Relaxed) {
+            return;
+        }
+        let monitors = get_available_monitors(&self.display);
+        let previous = mem::replace(&mut *self.monitors.lock().unwrap(), monitors.clone());
+        let mkmonitor = |monitor: MonitorId| ::MonitorId { inner: ::platform::MonitorId::X(monitor) };
+
+        for monitor in &previous {
+            if !monitors.iter().any(|m| m.is_same_output(monitor)) {
+                callback(Event::MonitorRemoved(mkmonitor(monitor.clone())));
+            }
+        }
+        for monitor in monitors {
+            match previous.iter().find(|m| m.is_same_output(&monitor)) {
+                None => callback(Event::MonitorAdded(mkmonitor(monitor))),
+                Some(previous) if !previous.is_same_configuration(&monitor) => {
+                    callback(Event::MonitorChanged(mkmonitor(monitor)))
+                },
+                Some(_) => (),
+            }
+        }
+    }
+
+    pub fn get_available_devices(&self) -> Vec<DeviceId> {
+        self.devices.lock().unwrap().keys().cloned().collect()
+    }
+
+    pub fn get_device_info(&self, device: &DeviceId) -> Option<::DeviceInfo> {
+        self.devices.lock().unwrap().get(device).map(|device| device.info.clone())
+    }
+
     fn init_device(&self, device: c_int) {
         let mut devices = self.devices.lock().unwrap();
         for info in DeviceInfo::get(&self.display, device).iter() {
@@ -725,7 +973,7 @@ impl Window {
 
             let im = (x_events_loop.display.xlib.XOpenIM)(x_events_loop.display.display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
             if im.is_null() {
-                panic!("XOpenIM failed");
+                return Err(CreationError::OsError("XOpenIM failed".to_owned()));
             }
             im
         };
@@ -737,20 +985,29 @@ impl Window {
                                               ffi::XIMPreeditNothing | ffi::XIMStatusNothing, b"clientWindow\0".as_ptr() as *const _,
                                               win.id().0, ptr::null::<()>());
             if ic.is_null() {
-                panic!("XCreateIC failed");
+                (x_events_loop.display.xlib.XCloseIM)(im);
+                return Err(CreationError::OsError("XCreateIC failed".to_owned()));
             }
             (x_events_loop.display.xlib.XSetICFocus)(ic);
-            x_events_loop.display.check_errors().expect("Failed to call XSetICFocus");
+            if let Err(err) = x_events_loop.display.check_errors() {
+                (x_events_loop.display.xlib.XDestroyIC)(ic);
+                (x_events_loop.display.xlib.XCloseIM)(im);
+                return Err(CreationError::X11(err));
+            }
             ic
         };
 
         x_events_loop.windows.lock().unwrap().insert(win.id(), WindowData {
+            window: Arc::downgrade(&win),
             im: im,
             ic: ic,
             ic_spot: ffi::XPoint {x: 0, y: 0},
             config: None,
             multitouch: window.multitouch,
             cursor_pos: None,
+            redraw_requested: false,
+            cursor_locked: false,
+            touches: HashMap::new(),
         });
 
         Ok(Window {
@@ -765,6 +1022,57 @@ impl Window {
         self.window.id()
     }
 
+    pub fn request_redraw(&self) {
+        if let (Some(windows), Some(display)) = (self.windows.upgrade(), self.display.upgrade()) {
+            {
+                let mut windows = windows.lock().unwrap();
+                let w = windows.get_mut(&self.window.id()).unwrap();
+                if w.redraw_requested {
+                    return;
+                }
+                w.redraw_requested = true;
+            }
+
+            // Send an empty client message to the window, so that the events loop wakes up if it
+            // is blocked and emits the event.
+            let window = self.window.id().0;
+            let mut xev = ffi::XClientMessageEvent {
+                type_: ffi::ClientMessage,
+                window: window,
+                format: 32,
+                message_type: 0,
+                serial: 0,
+                send_event: 0,
+                display: display.display,
+                data: unsafe { mem::zeroed() },
+            };
+
+            unsafe {
+                let xevent = &mut xev as *mut ffi::XClientMessageEvent as *mut ffi::XEvent;
+                (display.xlib.XSendEvent)(display.display, window, ffi::False, 0, xevent);
+                (display.xlib.XFlush)(display.display);
+                // the window may already be destroyed, the request is then simply dropped
+                let _ = display.check_errors();
+            }
+        }
+    }
+
+    pub fn set_cursor_state(&self, state: CursorState) -> Result<(), String> {
+        let result = self.window.set_cursor_state(state);
+        let locked = state == CursorState::Lock && result.is_ok();
+        if let Some(windows) = self.windows.upgrade() {
+            let mut windows = windows.lock().unwrap();
+            let w = windows.get_mut(&self.window.id()).unwrap();
+            w.cursor_locked = locked;
+        }
+        if locked {
+            if let Some((width, height)) = self.window.get_inner_size() {
+                let _ = self.window.set_cursor_position(width as i32 / 2, height as i32 / 2);
+            }
+        }
+        result
+    }
+
     #[inline]
     pub fn send_xim_spot(&self, x: i16, y: i16) {
         if let (Some(windows), Some(display)) = (self.windows.upgrade(), self.display.upgrade()) {
@@ -802,12 +1110,20 @@ impl Drop for Window {
 
 /// State maintained for translating window-related events
 struct WindowData {
+    // The window, to change the video mode of an exclusive fullscreen window with the focus.
+    window: Weak<Window2>,
     config: Option<WindowConfig>,
     im: ffi::XIM,
     ic: ffi::XIC,
     ic_spot: ffi::XPoint,
     multitouch: bool,
     cursor_pos: Option<(f64, f64)>,
+    // Whether a `RedrawRequested` event must be emitted at the end of the current batch.
+    redraw_requested: bool,
+    // Whether the cursor state is `CursorState::Lock`.
+    cursor_locked: bool,
+    // The active touch sequences, and whether the window is their owner.
+    touches: HashMap<u32, bool>,
 }
 
 // Required by ffi members
@@ -864,13 +1180,247 @@ struct XExtension {
     first_error_id: c_int,
 }
 
+// The width in pixels of the area of the screen a touchscreen is mapped to. Its coordinate
+// transformation matrix maps the device to a fraction of the screen, usually an output, and defaults
+// to the whole screen.
+fn mapped_width(display: &XConnection, deviceid: c_int) -> f64 {
+    unsafe {
+        let screen = (display.xlib.XDefaultScreen)(display.display);
+        let width = (display.xlib.XDisplayWidth)(display.display, screen) as f64;
+        let height = (display.xlib.XDisplayHeight)(display.display, screen) as f64;
+
+        let atom = (display.xlib.XInternAtom)(display.display, b"Coordinate Transformation Matrix\0".as_ptr() as *const c_char, ffi::True);
+        if atom == 0 {
+            return width;
+        }
+        let mut actual_type = 0;
+        let mut actual_format = 0;
+        let mut nitems = 0;
+        let mut bytes_after = 0;
+        let mut data: *mut c_uchar = ptr::null_mut();
+        let status = (display.xinput2.XIGetProperty)(display.display, deviceid, atom, 0, 9, ffi::False,
+                                                     ffi::AnyPropertyType as ffi::Atom, &mut actual_type,
+                                                     &mut actual_format, &mut nitems, &mut bytes_after, &mut data);
+        if status != ffi::Success as c_int || data.is_null() {
+            return width;
+        }
+        // the matrix holds 32 bits floats, row by row
+        let scale = if actual_format == 32 && nitems == 9 {
+            let matrix = slice::from_raw_parts(data as *const f32, 9);
+            // the length of the device's horizontal axis on the screen, which may be rotated
+            Some((matrix[0] as f64 * width).hypot(matrix[3] as f64 * height))
+        } else {
+            None
+        };
+        (display.xlib.XFree)(data as *mut _);
+        scale.unwrap_or(width)
+    }
+}
+
+// Returns the name of an atom, or `None` for the `None` atom.
+fn atom_name(display: &XConnection, atom: ffi::Atom) -> Option<String> {
+    if atom == 0 {
+        return None;
+    }
+    unsafe {
+        let name = (display.xlib.XGetAtomName)(display.display, atom);
+        if name.is_null() {
+            return None;
+        }
+        let string = CStr::from_ptr(name).to_string_lossy().into_owned();
+        (display.xlib.XFree)(name as *mut _);
+        Some(string)
+    }
+}
+
 fn mkwid(w: ffi::Window) -> ::WindowId { ::WindowId(::platform::WindowId::X(WindowId(w))) }
 fn mkdid(w: c_int) -> ::DeviceId { ::DeviceId(::platform::DeviceId::X(DeviceId(w))) }
 
 #[derive(Debug)]
 struct Device {
-    name: String,
+    info: ::DeviceInfo,
     scroll_axes: Vec<(i32, ScrollAxis)>,
+    // `Some` if the device is a tablet tool
+    pen: Option<PenAxes>,
+    // `Some` if the device is a touchscreen or a touchpad
+    touch: Option<TouchAxes>,
+}
+
+// A valuator of a device, with its last value.
+#[derive(Debug, Copy, Clone)]
+struct Valuator {
+    number: i32,
+    min: f64,
+    max: f64,
+    value: f64,
+}
+
+impl Valuator {
+    fn find(info: &::DeviceInfo, labels: &[&str]) -> Option<Valuator> {
+        labels.iter()
+            .filter_map(|&label| info.axes.iter().find(|axis| axis.label.as_ref().map(|l| &l[..]) == Some(label)))
+            .next()
+            .map(|axis| Valuator { number: axis.id as i32, min: axis.min, max: axis.max, value: axis.min })
+    }
+
+    // Maps the value to the range from 0.0 to 1.0.
+    fn normalized(&self) -> f64 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            self.value
+        }
+    }
+}
+
+// Updates the values of `axes` with the valuators of an event.
+fn update_valuators(valuators: &ffi::XIValuatorState, axes: &mut [&mut Option<Valuator>]) {
+    let mask = unsafe { slice::from_raw_parts(valuators.mask, valuators.mask_len as usize) };
+    let mut value = valuators.values;
+    for i in 0..valuators.mask_len*8 {
+        if ffi::XIMaskIsSet(mask, i) {
+            for axis in axes.iter_mut() {
+                if let Some(ref mut axis) = **axis {
+                    if axis.number == i {
+                        axis.value = unsafe { *value };
+                    }
+                }
+            }
+            value = unsafe { value.offset(1) };
+        }
+    }
+}
+
+// The valuators of a touch device that describe the contacts.
+#[derive(Debug, Clone)]
+struct TouchAxes {
+    contact: ContactAxes,
+    // converts the sizes in the units of the device to pixels
+    pixels_per_unit: Option<f64>,
+    // the contacts of the active touches, by touch id
+    touches: HashMap<c_int, ContactAxes>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ContactAxes {
+    pressure: Option<Valuator>,
+    major: Option<Valuator>,
+    minor: Option<Valuator>,
+    orientation: Option<Valuator>,
+}
+
+impl TouchAxes {
+    // `width` is the width in pixels of the area the device is mapped to, there is none for the
+    // devices that don't map to the screen, like touchpads.
+    fn new(info: &::DeviceInfo, width: Option<f64>) -> TouchAxes {
+        let pixels_per_unit = width.and_then(|width| Valuator::find(info, &["Abs MT Position X"])
+            .and_then(|x| if x.max > x.min { Some(width / (x.max - x.min)) } else { None }));
+        TouchAxes {
+            contact: ContactAxes {
+                pressure: Valuator::find(info, &["Abs MT Pressure", "Abs Pressure"]),
+                major: Valuator::find(info, &["Abs MT Touch Major"]),
+                minor: Valuator::find(info, &["Abs MT Touch Minor"]),
+                orientation: Valuator::find(info, &["Abs MT Orientation"]),
+            },
+            pixels_per_unit: pixels_per_unit,
+            touches: HashMap::new(),
+        }
+    }
+
+    // Returns the force, radius and orientation of a touch after an event.
+    fn update(&mut self, id: c_int, phase: TouchPhase, valuators: &ffi::XIValuatorState)
+        -> (Option<f64>, Option<(f64, f64)>, Option<f64>)
+    {
+        let mut contact = self.touches.get(&id).cloned().unwrap_or(self.contact);
+        {
+            let ContactAxes { ref mut pressure, ref mut major, ref mut minor, ref mut orientation } = contact;
+            update_valuators(valuators, &mut [pressure, major, minor, orientation]);
+        }
+        match phase {
+            TouchPhase::Started | TouchPhase::Moved => { self.touches.insert(id, contact); },
+            TouchPhase::Ended | TouchPhase::Cancelled => { self.touches.remove(&id); },
+        }
+
+        let radius = match (self.pixels_per_unit, contact.major) {
+            (Some(scale), Some(major)) => {
+                // the axes are the diameters of the contact area, the minor one defaults to the major
+                let minor = contact.minor.unwrap_or(major);
+                Some((major.value * scale / 2.0, minor.value * scale / 2.0))
+            },
+            _ => None,
+        };
+        // the orientation covers a half revolution
+        let orientation = contact.orientation.map(|axis| axis.normalized() * 180.0 - 90.0);
+        (contact.pressure.map(|axis| axis.normalized()), radius, orientation)
+    }
+}
+
+// The valuators of a tablet tool, by meaning. The events only carry the valuators that changed, so
+// the last values are kept.
+#[derive(Debug, Copy, Clone)]
+struct PenAxes {
+    tool: PenTool,
+    pressure: Option<Valuator>,
+    tilt_x: Option<Valuator>,
+    tilt_y: Option<Valuator>,
+    rotation: Option<Valuator>,
+    distance: Option<Valuator>,
+}
+
+impl PenAxes {
+    fn new(info: &::DeviceInfo) -> PenAxes {
+        // The drivers create a device for each tool, named after it.
+        let name = info.name.to_lowercase();
+        let tool = if name.contains("eraser") {
+            PenTool::Eraser
+        } else if name.contains("cursor") {
+            PenTool::Mouse
+        } else {
+            PenTool::Pen
+        };
+        PenAxes {
+            tool: tool,
+            pressure: Valuator::find(info, &["Abs Pressure"]),
+            tilt_x: Valuator::find(info, &["Abs Tilt X"]),
+            tilt_y: Valuator::find(info, &["Abs Tilt Y"]),
+            rotation: Valuator::find(info, &["Abs Rotary Z"]),
+            distance: Valuator::find(info, &["Abs Distance"]),
+        }
+    }
+
+    fn update(&mut self, valuators: &ffi::XIValuatorState) {
+        update_valuators(valuators, &mut [&mut self.pressure, &mut self.tilt_x, &mut self.tilt_y,
+                                          &mut self.rotation, &mut self.distance]);
+    }
+
+    // `buttons` is the state of the buttons before the event, and `change` the button that the
+    // event presses or releases.
+    fn pen(&self, device_id: ::DeviceId, phase: PenPhase, location: (f64, f64),
+           buttons: &ffi::XIButtonState, change: Option<(i32, bool)>) -> Pen
+    {
+        let mask = unsafe { slice::from_raw_parts(buttons.mask, buttons.mask_len as usize) };
+        let pressed = |button: i32| match change {
+            Some((changed, pressed)) if changed == button => pressed,
+            _ => button < buttons.mask_len * 8 && ffi::XIMaskIsSet(mask, button),
+        };
+        // the tip is the first button, the barrel buttons come next
+        let barrel_buttons = (pressed(2) as u32) | ((pressed(3) as u32) << 1);
+        Pen {
+            device_id: device_id,
+            phase: phase,
+            tool: self.tool,
+            location: location,
+            down: pressed(1),
+            barrel_buttons: barrel_buttons,
+            pressure: self.pressure.map(|axis| axis.normalized()),
+            tilt: match (self.tilt_x, self.tilt_y) {
+                (Some(x), Some(y)) => Some((x.value, y.value)),
+                _ => None,
+            },
+            rotation: self.rotation.map(|axis| axis.normalized() * 360.0),
+            distance: self.distance.map(|axis| axis.normalized()),
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -889,7 +1439,6 @@ enum ScrollOrientation {
 impl Device {
     fn new(el: &EventsLoop, info: &ffi::XIDeviceInfo) -> Self
     {
-        let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() };
         let mut scroll_axes = Vec::new();
 
         if Device::physical_device(info) {
@@ -927,9 +1476,24 @@ impl Device {
             }
         }
 
+        let device_info = Device::info(&el.display, info);
+        let pen = if device_info.kind == ::DeviceKind::Tablet {
+            Some(PenAxes::new(&device_info))
+        } else {
+            None
+        };
+        let touch = match device_info.kind {
+            ::DeviceKind::Touchscreen => {
+                Some(TouchAxes::new(&device_info, Some(mapped_width(&el.display, info.deviceid))))
+            },
+            ::DeviceKind::Touchpad => Some(TouchAxes::new(&device_info, None)),
+            _ => None,
+        };
         let mut device = Device {
-            name: name.into_owned(),
+            info: device_info,
             scroll_axes: scroll_axes,
+            pen: pen,
+            touch: touch,
         };
         device.reset_scroll_position(info);
         device
@@ -952,6 +1516,61 @@ impl Device {
         }
     }
 
+    fn info(display: &XConnection, info: &ffi::XIDeviceInfo) -> ::DeviceInfo {
+        let name = unsafe { CStr::from_ptr(info.name).to_string_lossy().into_owned() };
+        let mut axes = Vec::new();
+        let mut buttons = 0;
+        let mut keys = false;
+        let mut touch_mode = None;
+
+        for class_ptr in Device::classes(info) {
+            let class = unsafe { &**class_ptr };
+            match class._type {
+                ffi::XIValuatorClass => {
+                    let info = unsafe { mem::transmute::<&ffi::XIAnyClassInfo, &ffi::XIValuatorClassInfo>(class) };
+                    axes.push(::AxisInfo {
+                        id: info.number as ::AxisId,
+                        label: atom_name(display, info.label),
+                        min: info.min,
+                        max: info.max,
+                    });
+                }
+                ffi::XIButtonClass => {
+                    let info = unsafe { mem::transmute::<&ffi::XIAnyClassInfo, &ffi::XIButtonClassInfo>(class) };
+                    buttons = info.num_buttons as u32;
+                }
+                ffi::XIKeyClass => keys = true,
+                ffi::XITouchClass => {
+                    let info = unsafe { mem::transmute::<&ffi::XIAnyClassInfo, &ffi::XITouchClassInfo>(class) };
+                    touch_mode = Some(info.mode);
+                }
+                _ => {}
+            }
+        }
+
+        let pointer = info._use == ffi::XIMasterPointer || info._use == ffi::XISlavePointer;
+        let pressure = axes.iter().any(|axis| axis.label.as_ref().map(|l| &l[..]) == Some("Abs Pressure"));
+        // The touchpads driven by libinput or synaptics don't have a touch class, only their name
+        // tells them apart from the mice.
+        let lowercase_name = name.to_lowercase();
+        let kind = match touch_mode {
+            Some(ffi::XIDirectTouch) => ::DeviceKind::Touchscreen,
+            Some(_) => ::DeviceKind::Touchpad,
+            None if lowercase_name.contains("touchpad") || lowercase_name.contains("trackpad") => ::DeviceKind::Touchpad,
+            None if pointer && pressure => ::DeviceKind::Tablet,
+            None if pointer => ::DeviceKind::Mouse,
+            None if keys => ::DeviceKind::Keyboard,
+            None => ::DeviceKind::Other,
+        };
+
+        ::DeviceInfo {
+            name: name,
+            kind: kind,
+            axes: axes,
+            buttons: buttons,
+        }
+    }
+
     #[inline]
     fn physical_device(info: &ffi::XIDeviceInfo) -> bool {
         info._use == ffi::XISlaveKeyboard || info._use == ffi::XISlavePointer || info._use == ffi::XIFloatingSlave
@@ -1,7 +1,13 @@
+use std::collections::VecDeque;
 use std::sync::Arc;
-use std::slice;
+use std::{ptr, slice};
+use std::os::raw::{c_int, c_uchar, c_ulong};
 
-use super::XConnection;
+use {MonitorRotation, SubpixelLayout, VideoMode};
+use platform::MonitorId as PlatformMonitorId;
+use window::MonitorId as RootMonitorId;
+
+use super::{ffi, XConnection, XError};
 
 #[derive(Clone)]
 pub struct MonitorId {
@@ -15,13 +21,49 @@ pub struct MonitorId {
     position: (i32, i32),
     /// If the monitor is the primary one
     primary: bool,
+    /// The CRTC showing the monitor
+    crtc: ffi::RRCrtc,
+    /// The output of the monitor, whose modes the CRTC can be set to
+    output: ffi::RROutput,
+    /// The modes of the output
+    modes: Vec<Mode>,
+    /// The current mode of the CRTC
+    current_mode: Option<Mode>,
+    /// The depth of the X screen
+    bit_depth: u16,
+    /// The physical size of the monitor, in millimeters
+    physical_size_mm: (u32, u32),
+    /// The subpixel layout of the output
+    subpixel_layout: SubpixelLayout,
+    /// The rotation of the CRTC
+    rotation: MonitorRotation,
+    /// The manufacturer and the model of the monitor, from its EDID
+    make: Option<String>,
+    model: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Mode {
+    dimensions: (u32, u32),
+    refresh_rate: u16,
+}
+
+/// The configuration of a CRTC before its video mode was changed, to restore it.
+pub struct CrtcConfig {
+    crtc: ffi::RRCrtc,
+    position: (c_int, c_int),
+    mode: ffi::RRMode,
+    rotation: ffi::Rotation,
+    outputs: Vec<ffi::RROutput>,
 }
 
 pub fn get_available_monitors(x: &Arc<XConnection>) -> Vec<MonitorId> {
     let mut available = Vec::new();
     unsafe {
         let root = (x.xlib.XDefaultRootWindow)(x.display);
-        let resources = (x.xrandr.XRRGetScreenResources)(x.display, root);
+        // the current resources don't make the server probe the outputs, which can take a while
+        let resources = (x.xrandr.XRRGetScreenResourcesCurrent)(x.display, root);
+        let bit_depth = (x.xlib.XDefaultDepth)(x.display, (x.xlib.XDefaultScreen)(x.display)) as u16;
 
         let mut major = 0;
         let mut minor = 0;
@@ -32,16 +74,32 @@ pub fn get_available_monitors(x: &Arc<XConnection>) -> Vec<MonitorId> {
             let monitors = (x.xrandr.XRRGetMonitors)(x.display, root, 1, &mut nmonitors);
             for i in 0..nmonitors {
                 let monitor = *(monitors.offset(i as isize));
-                let output = (x.xrandr.XRRGetOutputInfo)(x.display, resources, *(monitor.outputs.offset(0)));
+                let outputid = *(monitor.outputs.offset(0));
+                let output = (x.xrandr.XRRGetOutputInfo)(x.display, resources, outputid);
                 let nameslice = slice::from_raw_parts((*output).name as *mut u8, (*output).nameLen as usize);
                 let name = String::from_utf8_lossy(nameslice).into_owned();
+                let crtc = (*output).crtc;
+                let (modes, current_mode, rotation) = get_modes(x, resources, output, crtc);
+                let physical_size_mm = ((*output).mm_width as u32, (*output).mm_height as u32);
+                let subpixel_layout = get_subpixel_layout((*output).subpixel_order);
                 (x.xrandr.XRRFreeOutputInfo)(output);
+                let (make, model) = get_edid_names(x, outputid);
                 available.push(MonitorId{
                     id: i as u32,
                     name,
                     dimensions: (monitor.width as u32, monitor.height as u32),
                     position: (monitor.x as i32, monitor.y as i32),
                     primary: (monitor.primary != 0),
+                    crtc,
+                    output: outputid,
+                    modes,
+                    current_mode,
+                    bit_depth,
+                    physical_size_mm,
+                    subpixel_layout,
+                    rotation,
+                    make,
+                    model,
                 });
             }
             (x.xrandr.XRRFreeMonitors)(monitors);
@@ -53,16 +111,31 @@ pub fn get_available_monitors(x: &Arc<XConnection>) -> Vec<MonitorId> {
                 let crtcid = *((*resources).crtcs.offset(i as isize));
                 let crtc = (x.xrandr.XRRGetCrtcInfo)(x.display, resources, crtcid);
                 if (*crtc).width > 0 && (*crtc).height > 0 && (*crtc).noutput > 0 {
-                    let output = (x.xrandr.XRRGetOutputInfo)(x.display, resources, *((*crtc).outputs.offset(0)));
+                    let outputid = *((*crtc).outputs.offset(0));
+                    let output = (x.xrandr.XRRGetOutputInfo)(x.display, resources, outputid);
                     let nameslice = slice::from_raw_parts((*output).name as *mut u8, (*output).nameLen as usize);
                     let name = String::from_utf8_lossy(nameslice).into_owned();
+                    let (modes, current_mode, rotation) = get_modes(x, resources, output, crtcid);
+                    let physical_size_mm = ((*output).mm_width as u32, (*output).mm_height as u32);
+                    let subpixel_layout = get_subpixel_layout((*output).subpixel_order);
                     (x.xrandr.XRRFreeOutputInfo)(output);
+                    let (make, model) = get_edid_names(x, outputid);
                     available.push(MonitorId{
                         id: crtcid as u32,
                         name,
                         dimensions: ((*crtc).width as u32, (*crtc).height as u32),
                         position: ((*crtc).x as i32, (*crtc).y as i32),
                         primary: true,
+                        crtc: crtcid,
+                        output: outputid,
+                        modes,
+                        current_mode,
+                        bit_depth,
+                        physical_size_mm,
+                        subpixel_layout,
+                        rotation,
+                        make,
+                        model,
                     });
                 }
                 (x.xrandr.XRRFreeCrtcInfo)(crtc);
@@ -103,4 +176,347 @@ impl MonitorId {
     pub fn get_hidpi_factor(&self) -> f32 {
         1.0
     }
+
+    /// Whether the monitors are shown by the same output.
+    pub fn is_same_output(&self, other: &MonitorId) -> bool {
+        self.output == other.output
+    }
+
+    /// Whether the monitors, assumed to be the same, are configured the same way.
+    pub fn is_same_configuration(&self, other: &MonitorId) -> bool {
+        self.name == other.name && self.dimensions == other.dimensions &&
+            self.position == other.position && self.primary == other.primary &&
+            self.crtc == other.crtc && self.current_mode == other.current_mode &&
+            self.rotation == other.rotation
+    }
+
+    pub fn get_physical_size_mm(&self) -> Option<(u32, u32)> {
+        match self.physical_size_mm {
+            (0, _) | (_, 0) => None,
+            size => Some(size),
+        }
+    }
+
+    pub fn get_refresh_rate(&self) -> Option<u16> {
+        // the refresh rate of a mode without timings is 0
+        self.current_mode.map(|mode| mode.refresh_rate).and_then(|rate| if rate == 0 { None } else { Some(rate) })
+    }
+
+    pub fn get_make(&self) -> Option<String> {
+        self.make.clone()
+    }
+
+    pub fn get_model(&self) -> Option<String> {
+        self.model.clone()
+    }
+
+    #[inline]
+    pub fn get_subpixel_layout(&self) -> SubpixelLayout {
+        self.subpixel_layout
+    }
+
+    #[inline]
+    pub fn get_rotation(&self) -> MonitorRotation {
+        self.rotation
+    }
+
+    pub fn get_video_modes(&self) -> VecDeque<VideoMode> {
+        self.modes.iter().map(|mode| self.make_video_mode(mode)).collect()
+    }
+
+    pub fn get_current_video_mode(&self) -> Option<VideoMode> {
+        self.current_mode.as_ref().map(|mode| self.make_video_mode(mode))
+    }
+
+    fn make_video_mode(&self, mode: &Mode) -> VideoMode {
+        VideoMode {
+            monitor: RootMonitorId { inner: PlatformMonitorId::X(self.clone()) },
+            dimensions: mode.dimensions,
+            refresh_rate: mode.refresh_rate,
+            bit_depth: self.bit_depth,
+        }
+    }
+}
+
+// The modes of an output, with the dimensions they have on its CRTC, the current one and the
+// rotation of the CRTC
+unsafe fn get_modes(x: &Arc<XConnection>, resources: *mut ffi::XRRScreenResources,
+                    output: *mut ffi::XRROutputInfo, crtcid: ffi::RRCrtc)
+                    -> (Vec<Mode>, Option<Mode>, MonitorRotation)
+{
+    let crtc = if crtcid != 0 { (x.xrandr.XRRGetCrtcInfo)(x.display, resources, crtcid) } else { ptr::null_mut() };
+    let (rotation, current) = if !crtc.is_null() {
+        let info = ((*crtc).rotation, (*crtc).mode);
+        (x.xrandr.XRRFreeCrtcInfo)(crtc);
+        info
+    } else {
+        (ffi::RR_Rotate_0 as ffi::Rotation, 0)
+    };
+    let rotated = is_rotated(rotation);
+
+    let all_modes = slice::from_raw_parts((*resources).modes, (*resources).nmode as usize);
+    let output_modes = slice::from_raw_parts((*output).modes, (*output).nmode as usize);
+    let mut modes = Vec::new();
+    let mut current_mode = None;
+    for mode in output_modes.iter().filter_map(|id| all_modes.iter().find(|mode| mode.id == *id)) {
+        let info = Mode {
+            dimensions: if rotated { (mode.height, mode.width) } else { (mode.width, mode.height) },
+            refresh_rate: get_refresh_rate(mode).round() as u16,
+        };
+        if mode.id == current {
+            current_mode = Some(info);
+        }
+        // modes can differ only by their timings
+        if !modes.contains(&info) {
+            modes.push(info);
+        }
+    }
+    // the reflections are ignored
+    let rotation = match rotation as c_int & 0xf {
+        ffi::RR_Rotate_90 => MonitorRotation::Rotate90,
+        ffi::RR_Rotate_180 => MonitorRotation::Rotate180,
+        ffi::RR_Rotate_270 => MonitorRotation::Rotate270,
+        _ => MonitorRotation::Normal,
+    };
+    (modes, current_mode, rotation)
+}
+
+// Converts the subpixel order of an output, which is one of the `SubPixel` constants of the
+// Render extension
+fn get_subpixel_layout(order: ffi::SubpixelOrder) -> SubpixelLayout {
+    match order {
+        1 => SubpixelLayout::HorizontalRgb,
+        2 => SubpixelLayout::HorizontalBgr,
+        3 => SubpixelLayout::VerticalRgb,
+        4 => SubpixelLayout::VerticalBgr,
+        5 => SubpixelLayout::None,
+        _ => SubpixelLayout::Unknown,
+    }
+}
+
+// The manufacturer ID and the model name in the EDID of an output, if it has one
+unsafe fn get_edid_names(x: &Arc<XConnection>, output: ffi::RROutput) -> (Option<String>, Option<String>) {
+    let atom = (x.xlib.XInternAtom)(x.display, b"EDID\0".as_ptr() as *const _, ffi::True);
+    if atom == 0 {
+        return (None, None);
+    }
+
+    let mut actual_type = 0;
+    let mut actual_format = 0;
+    let mut nitems = 0;
+    let mut bytes_after = 0;
+    let mut data: *mut c_uchar = ptr::null_mut();
+    (x.xrandr.XRRGetOutputProperty)(x.display, output, atom, 0, 128, ffi::False, ffi::False,
+                                    ffi::AnyPropertyType as ffi::Atom, &mut actual_type,
+                                    &mut actual_format, &mut nitems, &mut bytes_after, &mut data);
+    if data.is_null() {
+        return (None, None);
+    }
+    let names = if actual_format == 8 && nitems >= 128 {
+        parse_edid(slice::from_raw_parts(data, 128))
+    } else {
+        (None, None)
+    };
+    (x.xlib.XFree)(data as *mut _);
+    names
+}
+
+fn parse_edid(edid: &[u8]) -> (Option<String>, Option<String>) {
+    const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+    if edid.len() < 128 || edid[..8] != HEADER {
+        return (None, None);
+    }
+
+    // the manufacturer ID is made of three 5 bits letters, from 1 for 'A' to 26 for 'Z'
+    let id = (edid[8] as u16) << 8 | edid[9] as u16;
+    let letters = [(id >> 10) & 0x1f, (id >> 5) & 0x1f, id & 0x1f];
+    let make = if letters.iter().all(|&letter| letter >= 1 && letter <= 26) {
+        Some(letters.iter().map(|&letter| (b'@' + letter as u8) as char).collect::<String>())
+    } else {
+        None
+    };
+
+    // the model name is in one of the four display descriptors, terminated by a line feed
+    let model = edid[54..126].chunks(18)
+        .find(|descriptor| descriptor[0] == 0 && descriptor[1] == 0 && descriptor[3] == 0xfc)
+        .map(|descriptor| {
+            let name = descriptor[5..].split(|&c| c == b'\n').next().unwrap();
+            String::from_utf8_lossy(name).trim().to_owned()
+        });
+
+    (make, model)
+}
+
+// The refresh rate of a mode, in Hz
+fn get_refresh_rate(mode: &ffi::XRRModeInfo) -> f64 {
+    let mut vtotal = mode.vTotal as f64;
+    if mode.modeFlags & ffi::RR_DoubleScan as c_ulong != 0 {
+        vtotal *= 2.0;
+    }
+    if mode.modeFlags & ffi::RR_Interlace as c_ulong != 0 {
+        vtotal /= 2.0;
+    }
+    if mode.hTotal == 0 || vtotal == 0.0 {
+        return 0.0;
+    }
+    mode.dotClock as f64 / (mode.hTotal as f64 * vtotal)
+}
+
+// Whether a rotation swaps the width and the height of the modes
+fn is_rotated(rotation: ffi::Rotation) -> bool {
+    rotation & (ffi::RR_Rotate_90 | ffi::RR_Rotate_270) as ffi::Rotation != 0
+}
+
+impl MonitorId {
+    /// Switches the CRTC of the monitor to the mode of its output with the given dimensions and
+    /// the closest refresh rate, and returns the configuration to restore.
+    ///
+    /// Returns `None` if the output has no such mode, or if it can't be set.
+    pub fn set_video_mode(&self, x: &Arc<XConnection>, dimensions: (u32, u32), refresh_rate: u16)
+        -> Result<Option<CrtcConfig>, XError>
+    {
+        unsafe {
+            let root = (x.xlib.XDefaultRootWindow)(x.display);
+            let resources = (x.xrandr.XRRGetScreenResourcesCurrent)(x.display, root);
+            if resources.is_null() {
+                try!(x.check_errors());
+                return Ok(None);
+            }
+            let crtc = (x.xrandr.XRRGetCrtcInfo)(x.display, resources, self.crtc);
+            let output = (x.xrandr.XRRGetOutputInfo)(x.display, resources, self.output);
+            // the output may have been disconnected, or its CRTC disabled, since the monitor was listed
+            if crtc.is_null() || output.is_null() {
+                if !output.is_null() {
+                    (x.xrandr.XRRFreeOutputInfo)(output);
+                }
+                if !crtc.is_null() {
+                    (x.xrandr.XRRFreeCrtcInfo)(crtc);
+                }
+                (x.xrandr.XRRFreeScreenResources)(resources);
+                try!(x.check_errors());
+                return Ok(None);
+            }
+
+            let rotated = is_rotated((*crtc).rotation);
+            let modes = slice::from_raw_parts((*resources).modes, (*resources).nmode as usize);
+            let output_modes = slice::from_raw_parts((*output).modes, (*output).nmode as usize);
+            let mode = output_modes.iter()
+                .filter_map(|id| modes.iter().find(|mode| mode.id == *id))
+                .filter(|mode| {
+                    let size = if rotated { (mode.height, mode.width) } else { (mode.width, mode.height) };
+                    size == dimensions
+                })
+                .min_by_key(|mode| ((get_refresh_rate(mode) - refresh_rate as f64).abs() * 1000.0) as u32)
+                .map(|mode| mode.id);
+
+            // The screen isn't resized, so the CRTC must still fit in it
+            let screen = (x.xlib.XDefaultScreen)(x.display);
+            let fits = (*crtc).x + dimensions.0 as c_int <= (x.xlib.XDisplayWidth)(x.display, screen) &&
+                (*crtc).y + dimensions.1 as c_int <= (x.xlib.XDisplayHeight)(x.display, screen);
+
+            let config = CrtcConfig {
+                crtc: self.crtc,
+                position: ((*crtc).x, (*crtc).y),
+                mode: (*crtc).mode,
+                rotation: (*crtc).rotation,
+                outputs: slice::from_raw_parts((*crtc).outputs, (*crtc).noutput as usize).to_vec(),
+            };
+            let result = match mode {
+                Some(mode) if fits => {
+                    let mut outputs = config.outputs.clone();
+                    let status = (x.xrandr.XRRSetCrtcConfig)(
+                        x.display, resources, self.crtc, ffi::CurrentTime, config.position.0,
+                        config.position.1, mode, config.rotation, outputs.as_mut_ptr(),
+                        outputs.len() as c_int
+                    );
+                    if status == ffi::RRSetConfigSuccess { Some(config) } else { None }
+                },
+                _ => None,
+            };
+
+            (x.xrandr.XRRFreeOutputInfo)(output);
+            (x.xrandr.XRRFreeCrtcInfo)(crtc);
+            (x.xrandr.XRRFreeScreenResources)(resources);
+            try!(x.check_errors());
+            Ok(result)
+        }
+    }
+}
+
+impl CrtcConfig {
+    /// Sets the CRTC back to its previous configuration.
+    pub fn restore(&self, x: &Arc<XConnection>) -> Result<(), XError> {
+        unsafe {
+            let root = (x.xlib.XDefaultRootWindow)(x.display);
+            let resources = (x.xrandr.XRRGetScreenResourcesCurrent)(x.display, root);
+            if resources.is_null() {
+                try!(x.check_errors());
+                return Err(restore_error(ffi::RRSetConfigFailed));
+            }
+            let mut outputs = self.outputs.clone();
+            let status = (x.xrandr.XRRSetCrtcConfig)(
+                x.display, resources, self.crtc, ffi::CurrentTime, self.position.0, self.position.1,
+                self.mode, self.rotation, outputs.as_mut_ptr(), outputs.len() as c_int
+            );
+            (x.xrandr.XRRFreeScreenResources)(resources);
+            try!(x.check_errors());
+            if status != ffi::RRSetConfigSuccess {
+                return Err(restore_error(status));
+            }
+            Ok(())
+        }
+    }
+}
+
+// The error of a failed `XRRSetCrtcConfig` request, which XRandR reports in its reply rather than as
+// an X error
+fn restore_error(status: c_int) -> XError {
+    XError {
+        description: "Failed to restore the configuration of the CRTC".to_owned(),
+        error_code: status as u8,
+        request_code: 0,
+        minor_code: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_edid;
+
+    // The EDID of a Dell U2410
+    const EDID: [u8; 128] = [
+        0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x10, 0xac, 0x17, 0xa0, 0x53, 0x4c, 0x35, 0x30,
+        0x10, 0x14, 0x01, 0x03, 0x80, 0x34, 0x20, 0x78, 0xea, 0x1e, 0xc5, 0xae, 0x4f, 0x34, 0xb1, 0x26,
+        0x0e, 0x50, 0x54, 0xa5, 0x4b, 0x00, 0x81, 0x80, 0xa9, 0x40, 0xd1, 0x00, 0x71, 0x4f, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x28, 0x3c, 0x80, 0xa0, 0x70, 0xb0, 0x23, 0x40, 0x30, 0x20,
+        0x36, 0x00, 0x06, 0x44, 0x21, 0x00, 0x00, 0x1a, 0x00, 0x00, 0x00, 0xff, 0x00, 0x46, 0x35, 0x32,
+        0x35, 0x4d, 0x30, 0x41, 0x33, 0x30, 0x35, 0x34, 0x4c, 0x0a, 0x00, 0x00, 0x00, 0xfc, 0x00, 0x44,
+        0x45, 0x4c, 0x4c, 0x20, 0x55, 0x32, 0x34, 0x31, 0x30, 0x0a, 0x20, 0x20, 0x00, 0x00, 0x00, 0xfd,
+        0x00, 0x38, 0x4c, 0x1e, 0x51, 0x11, 0x00, 0x0a, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00, 0xe0,
+    ];
+
+    #[test]
+    fn parses_edid() {
+        assert_eq!(parse_edid(&EDID), (Some("DEL".to_owned()), Some("DELL U2410".to_owned())));
+    }
+
+    #[test]
+    fn rejects_invalid_header() {
+        let mut edid = EDID;
+        edid[0] = 0xff;
+        assert_eq!(parse_edid(&edid), (None, None));
+        assert_eq!(parse_edid(&EDID[..64]), (None, None));
+    }
+
+    #[test]
+    fn rejects_invalid_manufacturer_id() {
+        let mut edid = EDID;
+        // the first letter is 0
+        edid[8] = 0x00;
+        assert_eq!(parse_edid(&edid), (None, Some("DELL U2410".to_owned())));
+        // the last letter is 27
+        edid[8] = 0x10;
+        edid[9] = 0x1b;
+        assert_eq!(parse_edid(&edid), (None, Some("DELL U2410".to_owned())));
+    }
 }
@@ -1,6 +1,5 @@
 use MouseCursor;
 use CreationError;
-use CreationError::OsError;
 use libc;
 use std::borrow::Borrow;
 use std::{mem, cmp};
@@ -10,6 +9,8 @@ use std::thread;
 use std::time::Duration;
 
 use CursorState;
+use Fullscreen;
+use VideoMode;
 use WindowAttributes;
 use platform::PlatformSpecificWindowBuilderAttributes;
 
@@ -17,10 +18,10 @@ use platform::MonitorId as PlatformMonitorId;
 use platform::x11::MonitorId as X11MonitorId;
 use window::MonitorId as RootMonitorId;
 
-use platform::x11::monitor::get_available_monitors;
+use platform::x11::monitor::{CrtcConfig, get_available_monitors};
 
 use super::{ffi};
-use super::{XConnection, WindowId, EventsLoop};
+use super::{XConnection, XError, WindowId, EventsLoop};
 
 // TODO: remove me
 fn with_c_str<F, T>(s: &str, f: F) -> T where F: FnOnce(*const libc::c_char) -> T {
@@ -29,6 +30,13 @@ fn with_c_str<F, T>(s: &str, f: F) -> T where F: FnOnce(*const libc::c_char) ->
     f(c_str.as_ptr())
 }
 
+// Logs the error of a request made by a method that has no way to report it.
+fn log_error(result: Result<(), XError>, message: &str) {
+    if let Err(err) = result {
+        eprintln!("[winit] {}: {}", message, err);
+    }
+}
+
 pub struct XWindow {
     display: Arc<XConnection>,
     window: ffi::Window,
@@ -39,12 +47,37 @@ pub struct XWindow {
 unsafe impl Send for XWindow {}
 unsafe impl Sync for XWindow {}
 
+impl Drop for XWindow {
+    fn drop(&mut self) {
+        unsafe {
+            (self.display.xlib.XDestroyWindow)(self.display.display, self.window);
+            // Nothing we can do about a failure at this point
+            let _ = self.display.check_errors();
+        }
+    }
+}
+
 unsafe impl Send for Window2 {}
 unsafe impl Sync for Window2 {}
 
 pub struct Window2 {
     pub x: Arc<XWindow>,
     cursor_state: Mutex<CursorState>,
+    exclusive: Mutex<Option<Exclusive>>,
+}
+
+// The state of an exclusive fullscreen window
+struct Exclusive {
+    video_mode: VideoMode,
+    // The configuration of the CRTC the video mode replaced, `None` while the video mode isn't set
+    saved: Option<CrtcConfig>,
+}
+
+impl Drop for Window2 {
+    fn drop(&mut self) {
+        // Nothing we can do about a failure at this point
+        let _ = self.leave_video_mode();
+    }
 }
 
 impl Window2 {
@@ -71,6 +104,12 @@ impl Window2 {
 
         };
 
+        // a wayland monitor can't be used to make an X11 window fullscreen
+        match window_attrs.fullscreen.as_ref().map(Fullscreen::get_monitor) {
+            Some(&RootMonitorId { inner: PlatformMonitorId::X(_) }) | None => (),
+            _ => return Err(CreationError::UnsupportedAttribute { name: "fullscreen" }),
+        }
+
         let screen_id = match pl_attribs.screen_id {
             Some(id) => id,
             None => unsafe { (display.xlib.XDefaultScreen)(display.display) },
@@ -121,21 +160,30 @@ impl Window2 {
                 },
                 window_attributes,
                 &mut set_win_attr);
-            display.check_errors().expect("Failed to call XCreateWindow");
+            try!(display.check_errors().map_err(CreationError::X11));
             win
         };
 
+        // From now on, the window is destroyed when `x_window` is dropped, including if we return
+        // early because one of the following requests failed.
+        let x_window = XWindow {
+            display: display.clone(),
+            window,
+            root,
+            screen_id,
+        };
+
         // Set ICCCM WM_CLASS property based on initial window title
         // Must be done *before* mapping the window by ICCCM 4.1.2.5
         unsafe {
-            with_c_str(&*window_attrs.title, |c_name| {
+            try!(with_c_str(&*window_attrs.title, |c_name| {
                 let hint = (display.xlib.XAllocClassHint)();
                 (*hint).res_name = c_name as *mut libc::c_char;
                 (*hint).res_class = c_name as *mut libc::c_char;
                 (display.xlib.XSetClassHint)(display.display, window, hint);
-                display.check_errors().expect("Failed to call XSetClassHint");
                 (display.xlib.XFree)(hint as *mut _);
-            });
+                display.check_errors()
+            }).map_err(CreationError::X11));
         }
 
         // set visibility
@@ -145,15 +193,15 @@ impl Window2 {
                 (display.xlib.XFlush)(display.display);
             }
 
-            display.check_errors().expect("Failed to set window visibility");
+            try!(display.check_errors().map_err(CreationError::X11));
         }
 
         // Opt into handling window close
         unsafe {
             (display.xlib.XSetWMProtocols)(display.display, window, &ctx.wm_delete_window as *const _ as *mut _, 1);
-            display.check_errors().expect("Failed to call XSetWMProtocols");
+            try!(display.check_errors().map_err(CreationError::X11));
             (display.xlib.XFlush)(display.display);
-            display.check_errors().expect("Failed to call XFlush");
+            try!(display.check_errors().map_err(CreationError::X11));
         }
 
         // Attempt to make keyboard input repeat detectable
@@ -161,7 +209,7 @@ impl Window2 {
             let mut supported_ptr = ffi::False;
             (display.xlib.XkbSetDetectableAutoRepeat)(display.display, ffi::True, &mut supported_ptr);
             if supported_ptr == ffi::False {
-                return Err(OsError(format!("XkbSetDetectableAutoRepeat failed")));
+                return Err(CreationError::OsError(format!("XkbSetDetectableAutoRepeat failed")));
             }
         }
 
@@ -182,7 +230,7 @@ impl Window2 {
         }
         unsafe {
             (display.xlib.XSetNormalHints)(display.display, window, &mut size_hints);
-            display.check_errors().expect("Failed to call XSetNormalHints");
+            try!(display.check_errors().map_err(CreationError::X11));
         }
 
         // Select XInput2 events
@@ -192,7 +240,11 @@ impl Window2 {
                 // | ffi::XI_KeyPressMask | ffi::XI_KeyReleaseMask
                 | ffi::XI_EnterMask | ffi::XI_LeaveMask
                 | ffi::XI_FocusInMask | ffi::XI_FocusOutMask
-                | if window_attrs.multitouch { ffi::XI_TouchBeginMask | ffi::XI_TouchUpdateMask | ffi::XI_TouchEndMask } else { 0 };
+                | if window_attrs.multitouch {
+                    // the ownership events tell which touch sequences are taken over by other clients
+                    ffi::XI_TouchBeginMask | ffi::XI_TouchUpdateMask | ffi::XI_TouchEndMask
+                        | ffi::XI_TouchOwnershipChangedMask
+                } else { 0 };
             unsafe {
                 let mut event_mask = ffi::XIEventMask{
                     deviceid: ffi::XIAllMasterDevices,
@@ -201,23 +253,25 @@ impl Window2 {
                 };
                 (display.xinput2.XISelectEvents)(display.display, window,
                                                  &mut event_mask as *mut ffi::XIEventMask, 1);
+                // the touch masks require XInput 2.2, which the server may not support
+                try!(display.check_errors().map_err(|err| if window_attrs.multitouch {
+                    CreationError::UnsupportedAttribute { name: "multitouch" }
+                } else {
+                    CreationError::X11(err)
+                }));
             };
         }
 
         let window = Window2 {
-            x: Arc::new(XWindow {
-                display: display.clone(),
-                window,
-                root,
-                screen_id,
-            }),
+            x: Arc::new(x_window),
             cursor_state: Mutex::new(CursorState::Normal),
+            exclusive: Mutex::new(None),
         };
 
-        window.set_title(&window_attrs.title);
-        window.set_decorations(window_attrs.decorations);
-        window.set_maximized(window_attrs.maximized);
-        window.set_fullscreen(window_attrs.fullscreen.clone());
+        try!(window.set_title_inner(&window_attrs.title).map_err(CreationError::X11));
+        try!(window.set_decorations_inner(window_attrs.decorations).map_err(CreationError::X11));
+        try!(window.set_maximized_inner(window_attrs.maximized).map_err(CreationError::X11));
+        try!(window.set_fullscreen_inner(window_attrs.fullscreen.clone()).map_err(CreationError::X11));
 
         if window_attrs.visible {
             unsafe {
@@ -228,7 +282,7 @@ impl Window2 {
                 loop {
                     let mut window_attributes = mem::uninitialized();
                     (display.xlib.XGetWindowAttributes)(display.display, x_window.window, &mut window_attributes);
-                    display.check_errors().expect("Failed to call XGetWindowAttributes");
+                    try!(display.check_errors().map_err(CreationError::X11));
 
                     if window_attributes.map_state == ffi::IsViewable {
                         (display.xlib.XSetInputFocus)(
@@ -237,7 +291,7 @@ impl Window2 {
                             ffi::RevertToParent,
                             ffi::CurrentTime
                         );
-                        display.check_errors().expect("Failed to call XSetInputFocus");
+                        try!(display.check_errors().map_err(CreationError::X11));
                         break;
                     }
 
@@ -251,19 +305,21 @@ impl Window2 {
         Ok(window)
     }
 
-    fn set_netwm(display: &Arc<XConnection>, window: ffi::Window, root: ffi::Window, property: &str, val: bool) {
+    fn set_netwm(display: &Arc<XConnection>, window: ffi::Window, root: ffi::Window, property: &str, val: bool)
+                 -> Result<(), XError>
+    {
         let state_atom = unsafe {
             with_c_str("_NET_WM_STATE", |state|
                 (display.xlib.XInternAtom)(display.display, state, 0)
             )
         };
-        display.check_errors().expect("Failed to call XInternAtom");
+        try!(display.check_errors());
         let atom = unsafe {
             with_c_str(property, |state|
                 (display.xlib.XInternAtom)(display.display, state, 0)
             )
         };
-        display.check_errors().expect("Failed to call XInternAtom");
+        try!(display.check_errors());
 
         let client_message_event = ffi::XClientMessageEvent {
             type_: ffi::ClientMessage,
@@ -292,24 +348,89 @@ impl Window2 {
                 ffi::SubstructureRedirectMask | ffi::SubstructureNotifyMask,
                 &mut x_event as *mut _
             );
-            display.check_errors().expect("Failed to call XSendEvent");
+            display.check_errors()
         }
     }
 
-    pub fn set_fullscreen(&self, monitor: Option<RootMonitorId>) {
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        log_error(self.set_fullscreen_inner(fullscreen), "Failed to change fullscreen state");
+    }
+
+    fn set_fullscreen_inner(&self, fullscreen: Option<Fullscreen>) -> Result<(), XError> {
+        self.restore_video_mode();
+        *self.exclusive.lock().unwrap() = None;
+
+        let monitor = match fullscreen {
+            None => return self.set_fullscreen_hint(false),
+            Some(ref fullscreen) => fullscreen.get_monitor().clone(),
+        };
         match monitor {
-            None => {
-                self.set_fullscreen_hint(false);
-            },
-            Some(RootMonitorId { inner: PlatformMonitorId::X(monitor) }) => {
+            RootMonitorId { inner: PlatformMonitorId::X(monitor) } => {
+                if let Some(Fullscreen::Exclusive(video_mode)) = fullscreen {
+                    *self.exclusive.lock().unwrap() = Some(Exclusive {
+                        video_mode: video_mode,
+                        saved: None,
+                    });
+                    self.enter_video_mode_or_borderless();
+                }
                 let screenpos = monitor.get_position();
-                self.set_position(screenpos.0 as i32, screenpos.1 as i32);
-                self.set_fullscreen_hint(true);
+                unsafe { (self.x.display.xlib.XMoveWindow)(self.x.display.display, self.x.window, screenpos.0, screenpos.1); }
+                try!(self.x.display.check_errors());
+                self.set_fullscreen_hint(true)
             }
             _ => {
                 eprintln!("[winit] Something's broken, got an unknown fullscreen state in X11");
+                Ok(())
+            }
+        }
+    }
+
+    /// Switches to the video mode of an exclusive fullscreen window when it gains the focus, and
+    /// back to the original one when it loses it.
+    ///
+    /// The monitors can change at any time, so the errors are only logged. A window whose video
+    /// mode can't be set stays fullscreen in the current one, like a borderless window.
+    pub fn update_video_mode(&self, focused: bool) {
+        if focused {
+            self.enter_video_mode_or_borderless();
+        } else {
+            self.restore_video_mode();
+        }
+    }
+
+    fn enter_video_mode_or_borderless(&self) {
+        if let Err(err) = self.enter_video_mode() {
+            eprintln!("[winit] Failed to set the video mode of a fullscreen window: {}", err);
+            *self.exclusive.lock().unwrap() = None;
+        }
+    }
+
+    fn restore_video_mode(&self) {
+        log_error(self.leave_video_mode(), "Failed to restore the video mode of a monitor");
+    }
+
+    fn enter_video_mode(&self) -> Result<(), XError> {
+        let mut exclusive = self.exclusive.lock().unwrap();
+        if let Some(ref mut exclusive) = *exclusive {
+            if exclusive.saved.is_some() {
+                return Ok(());
+            }
+            if let PlatformMonitorId::X(ref monitor) = exclusive.video_mode.monitor.inner {
+                let video_mode = &exclusive.video_mode;
+                exclusive.saved = try!(monitor.set_video_mode(
+                    &self.x.display, video_mode.dimensions, video_mode.refresh_rate
+                ));
             }
         }
+        Ok(())
+    }
+
+    fn leave_video_mode(&self) -> Result<(), XError> {
+        let saved = self.exclusive.lock().unwrap().as_mut().and_then(|exclusive| exclusive.saved.take());
+        match saved {
+            Some(saved) => saved.restore(&self.x.display),
+            None => Ok(()),
+        }
     }
 
     pub fn get_current_monitor(&self) -> X11MonitorId {
@@ -350,24 +471,32 @@ impl Window2 {
     }
 
     pub fn set_maximized(&self, maximized: bool) {
-        Window2::set_netwm(&self.x.display, self.x.window, self.x.root, "_NET_WM_STATE_MAXIMIZED_HORZ", maximized);
-        Window2::set_netwm(&self.x.display, self.x.window, self.x.root, "_NET_WM_STATE_MAXIMIZED_VERT", maximized);
+        log_error(self.set_maximized_inner(maximized), "Failed to change maximized state");
+    }
+
+    fn set_maximized_inner(&self, maximized: bool) -> Result<(), XError> {
+        try!(Window2::set_netwm(&self.x.display, self.x.window, self.x.root, "_NET_WM_STATE_MAXIMIZED_HORZ", maximized));
+        Window2::set_netwm(&self.x.display, self.x.window, self.x.root, "_NET_WM_STATE_MAXIMIZED_VERT", maximized)
     }
 
-    fn set_fullscreen_hint(&self, fullscreen: bool) {
-        Window2::set_netwm(&self.x.display, self.x.window, self.x.root, "_NET_WM_STATE_FULLSCREEN", fullscreen);
+    fn set_fullscreen_hint(&self, fullscreen: bool) -> Result<(), XError> {
+        Window2::set_netwm(&self.x.display, self.x.window, self.x.root, "_NET_WM_STATE_FULLSCREEN", fullscreen)
     }
 
     pub fn set_title(&self, title: &str) {
+        log_error(self.set_title_inner(title), "Failed to set window title");
+    }
+
+    fn set_title_inner(&self, title: &str) -> Result<(), XError> {
         let wm_name = unsafe {
             (self.x.display.xlib.XInternAtom)(self.x.display.display, b"_NET_WM_NAME\0".as_ptr() as *const _, 0)
         };
-        self.x.display.check_errors().expect("Failed to call XInternAtom");
+        try!(self.x.display.check_errors());
 
         let wm_utf8_string = unsafe {
             (self.x.display.xlib.XInternAtom)(self.x.display.display, b"UTF8_STRING\0".as_ptr() as *const _, 0)
         };
-        self.x.display.check_errors().expect("Failed to call XInternAtom");
+        try!(self.x.display.check_errors());
 
         with_c_str(title, |c_title| unsafe {
             (self.x.display.xlib.XStoreName)(self.x.display.display, self.x.window, c_title);
@@ -378,11 +507,14 @@ impl Window2 {
                                             c_title as *const u8, len as libc::c_int);
             (self.x.display.xlib.XFlush)(self.x.display.display);
         });
-        self.x.display.check_errors().expect("Failed to set window title");
-
+        self.x.display.check_errors()
     }
 
     pub fn set_decorations(&self, decorations: bool) {
+        log_error(self.set_decorations_inner(decorations), "Failed to set decorations");
+    }
+
+    fn set_decorations_inner(&self, decorations: bool) -> Result<(), XError> {
         #[repr(C)]
         struct MotifWindowHints {
             flags: u32,
@@ -395,7 +527,7 @@ impl Window2 {
         let wm_hints = unsafe {
             (self.x.display.xlib.XInternAtom)(self.x.display.display, b"_MOTIF_WM_HINTS\0".as_ptr() as *const _, 0)
         };
-        self.x.display.check_errors().expect("Failed to call XInternAtom");
+        try!(self.x.display.check_errors());
 
         if !decorations {
             let hints = MotifWindowHints {
@@ -421,14 +553,14 @@ impl Window2 {
             }
         }
 
-        self.x.display.check_errors().expect("Failed to set decorations");
+        self.x.display.check_errors()
     }
 
     pub fn show(&self) {
         unsafe {
             (self.x.display.xlib.XMapRaised)(self.x.display.display, self.x.window);
             (self.x.display.xlib.XFlush)(self.x.display.display);
-            self.x.display.check_errors().expect("Failed to call XMapRaised");
+            log_error(self.x.display.check_errors(), "Failed to call XMapRaised");
         }
     }
 
@@ -436,7 +568,7 @@ impl Window2 {
         unsafe {
             (self.x.display.xlib.XUnmapWindow)(self.x.display.display, self.x.window);
             (self.x.display.xlib.XFlush)(self.x.display.display);
-            self.x.display.check_errors().expect("Failed to call XUnmapWindow");
+            log_error(self.x.display.check_errors(), "Failed to call XUnmapWindow");
         }
     }
 
@@ -470,7 +602,7 @@ impl Window2 {
 
     pub fn set_position(&self, x: i32, y: i32) {
         unsafe { (self.x.display.xlib.XMoveWindow)(self.x.display.display, self.x.window, x as libc::c_int, y as libc::c_int); }
-        self.x.display.check_errors().expect("Failed to call XMoveWindow");
+        log_error(self.x.display.check_errors(), "Failed to call XMoveWindow");
     }
 
     #[inline]
@@ -486,7 +618,7 @@ impl Window2 {
     #[inline]
     pub fn set_inner_size(&self, x: u32, y: u32) {
         unsafe { (self.x.display.xlib.XResizeWindow)(self.x.display.display, self.x.window, x as libc::c_uint, y as libc::c_uint); }
-        self.x.display.check_errors().expect("Failed to call XResizeWindow");
+        log_error(self.x.display.check_errors(), "Failed to call XResizeWindow");
     }
 
     #[inline]
@@ -590,7 +722,7 @@ impl Window2 {
             if xcursor != 0 {
                 (self.x.display.xlib.XFreeCursor)(self.x.display.display, xcursor);
             }
-            self.x.display.check_errors().expect("Failed to set or free the cursor");
+            log_error(self.x.display.check_errors(), "Failed to set or free the cursor");
         }
     }
 
@@ -640,19 +772,18 @@ impl Window2 {
     }
 
     pub fn set_cursor_state(&self, state: CursorState) -> Result<(), String> {
-        use CursorState::{ Grab, Normal, Hide };
+        use CursorState::{ Grab, Normal, Hide, Confine, Lock };
 
         let mut cursor_state = self.cursor_state.lock().unwrap();
-        match (state, *cursor_state) {
-            (Normal, Normal) | (Hide, Hide) | (Grab, Grab) => return Ok(()),
-            _ => {},
+        if state == *cursor_state {
+            return Ok(());
         }
 
         match *cursor_state {
-            Grab => {
+            Grab | Confine | Lock => {
                 unsafe {
                     (self.x.display.xlib.XUngrabPointer)(self.x.display.display, ffi::CurrentTime);
-                    self.x.display.check_errors().expect("Failed to call XUngrabPointer");
+                    try!(self.x.display.check_errors().map_err(|err| format!("Failed to call XUngrabPointer: {}", err)));
                 }
             },
             Normal => {},
@@ -675,13 +806,16 @@ impl Window2 {
                     if cursor != 0 {
                         (self.x.display.xlib.XFreeCursor)(self.x.display.display, cursor);
                     }
-                    self.x.display.check_errors().expect("Failed to call XDefineCursor or free the empty cursor");
+                    self.x.display.check_errors()
+                        .map_err(|err| format!("Failed to call XDefineCursor or free the empty cursor: {}", err))
                 }
-                Ok(())
             },
-            Grab => {
+            Grab | Confine | Lock => {
                 unsafe {
-                    match (self.x.display.xlib.XGrabPointer)(
+                    // A locked cursor is hidden for the duration of the grab. It is kept in place by
+                    // the events loop, which warps it back to the center of the window.
+                    let cursor = if state == Lock { self.create_empty_cursor() } else { 0 };
+                    let result = (self.x.display.xlib.XGrabPointer)(
                         self.x.display.display, self.x.window, ffi::True,
                         (ffi::ButtonPressMask | ffi::ButtonReleaseMask | ffi::EnterWindowMask |
                         ffi::LeaveWindowMask | ffi::PointerMotionMask | ffi::PointerMotionHintMask |
@@ -689,8 +823,12 @@ impl Window2 {
                         ffi::Button4MotionMask | ffi::Button5MotionMask | ffi::ButtonMotionMask |
                         ffi::KeymapStateMask) as libc::c_uint,
                         ffi::GrabModeAsync, ffi::GrabModeAsync,
-                        self.x.window, 0, ffi::CurrentTime
-                    ) {
+                        self.x.window, cursor, ffi::CurrentTime
+                    );
+                    if cursor != 0 {
+                        (self.x.display.xlib.XFreeCursor)(self.x.display.display, cursor);
+                    }
+                    match result {
                         ffi::GrabSuccess => Ok(()),
                         ffi::AlreadyGrabbed | ffi::GrabInvalidTime |
                         ffi::GrabNotViewable | ffi::GrabFrozen
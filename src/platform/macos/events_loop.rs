@@ -1,4 +1,4 @@
-use {ControlFlow, EventsLoopClosed};
+use {ControlFlow, EventsLoopClosed, EventsLoopCreationError};
 use cocoa::{self, appkit, foundation};
 use cocoa::appkit::{NSApplication, NSEvent, NSEventMask, NSEventModifierFlags, NSEventPhase, NSView, NSWindow};
 use events::{self, ElementState, Event, MouseButton, TouchPhase, WindowEvent, DeviceEvent, ModifiersState, KeyboardInput};
@@ -162,11 +162,11 @@ impl UserCallback {
 
 impl EventsLoop {
 
-    pub fn new() -> Self {
-        EventsLoop {
+    pub fn new() -> Result<Self, EventsLoopCreationError> {
+        Ok(EventsLoop {
             shared: Arc::new(Shared::new()),
             modifiers: Modifiers::new(),
-        }
+        })
     }
 
     pub fn poll_events<F>(&mut self, mut callback: F)
@@ -210,6 +210,36 @@ impl EventsLoop {
         self.shared.user_callback.drop();
     }
 
+    pub fn wait_events_timeout<F>(&mut self, timeout: std::time::Duration, callback: F)
+        where F: FnMut(Event),
+    {
+        unsafe {
+            if !msg_send![cocoa::base::class("NSThread"), isMainThread] {
+                panic!("Events can only be polled from the main thread on macOS");
+            }
+
+            // Only block if there is nothing left to deliver.
+            if self.shared.pending_events.lock().unwrap().is_empty() {
+                let pool = foundation::NSAutoreleasePool::new(cocoa::base::nil);
+
+                let seconds = timeout.as_secs() as f64 + timeout.subsec_nanos() as f64 * 1e-9;
+                let date: cocoa::base::id = msg_send![cocoa::base::class("NSDate"),
+                                                      dateWithTimeIntervalSinceNow:seconds];
+
+                // Wait for the next event without dequeuing it, `poll_events` will handle it.
+                let _ = appkit::NSApp().nextEventMatchingMask_untilDate_inMode_dequeue_(
+                    NSEventMask::NSAnyEventMask.bits() | NSEventMask::NSEventMaskPressure.bits(),
+                    date,
+                    foundation::NSDefaultRunLoopMode,
+                    cocoa::base::NO);
+
+                let _: () = msg_send![pool, release];
+            }
+        }
+
+        self.poll_events(callback);
+    }
+
     pub fn run_forever<F>(&mut self, mut callback: F)
         where F: FnMut(Event) -> ControlFlow
     {
@@ -584,6 +614,14 @@ impl EventsLoop {
         }
     }
 
+    pub fn get_available_devices(&self) -> VecDeque<super::DeviceId> {
+        VecDeque::new()
+    }
+
+    pub fn get_device_info(&self, _: &super::DeviceId) -> Option<::DeviceInfo> {
+        None
+    }
+
     pub fn create_proxy(&self) -> Proxy {
         Proxy {}
     }
@@ -55,4 +55,44 @@ impl MonitorId {
     pub fn get_hidpi_factor(&self) -> f32 {
         1.0
     }
+
+    #[inline]
+    pub fn get_physical_size_mm(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    #[inline]
+    pub fn get_refresh_rate(&self) -> Option<u16> {
+        None
+    }
+
+    #[inline]
+    pub fn get_make(&self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    pub fn get_model(&self) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    pub fn get_subpixel_layout(&self) -> ::SubpixelLayout {
+        ::SubpixelLayout::Unknown
+    }
+
+    #[inline]
+    pub fn get_rotation(&self) -> ::MonitorRotation {
+        ::MonitorRotation::Normal
+    }
+
+    #[inline]
+    pub fn get_video_modes(&self) -> VecDeque<::VideoMode> {
+        VecDeque::new()
+    }
+
+    #[inline]
+    pub fn get_current_video_mode(&self) -> Option<::VideoMode> {
+        None
+    }
 }
@@ -1,4 +1,4 @@
-use {CreationError, Event, WindowEvent, WindowId, MouseCursor, CursorState};
+use {CreationError, Event, Fullscreen, WindowEvent, WindowId, MouseCursor, CursorState};
 use CreationError::OsError;
 use libc;
 
@@ -383,8 +383,8 @@ impl Window2 {
 
     fn create_window(attrs: &WindowAttributes) -> Option<IdRef> {
         unsafe {
-            let screen = match attrs.fullscreen {
-                Some(ref monitor_id) => {
+            let screen = match attrs.fullscreen.as_ref().map(Fullscreen::get_monitor) {
+                Some(monitor_id) => {
                     let native_id = monitor_id.inner.get_native_identifier();
                     let matching_screen = {
                         let screens = appkit::NSScreen::screens(nil);
@@ -471,6 +471,31 @@ impl Window2 {
         }
     }
 
+    pub fn request_redraw(&self) {
+        if let Some(shared) = self.delegate.state.shared.upgrade() {
+            let window_id = WindowId(self.id());
+            {
+                let mut pending_events = shared.pending_events.lock().unwrap();
+                // Merge with a request that hasn't been delivered yet.
+                let already_requested = pending_events.iter().any(|event| match *event {
+                    Event::WindowEvent { window_id: wid, event: WindowEvent::RedrawRequested } => {
+                        wid == window_id
+                    },
+                    _ => false,
+                });
+                if already_requested {
+                    return;
+                }
+                pending_events.push_back(Event::WindowEvent {
+                    window_id: window_id,
+                    event: WindowEvent::RedrawRequested,
+                });
+            }
+            // Wake up the events loop so that the pending event is delivered.
+            let _ = super::events_loop::Proxy {}.wakeup();
+        }
+    }
+
     pub fn set_title(&self, title: &str) {
         unsafe {
             let title = IdRef::new(NSString::alloc(nil).init_str(title));
@@ -603,11 +628,12 @@ impl Window2 {
                 let _: () = unsafe { msg_send![cls, hide] };
                 Ok(())
             },
-            CursorState::Grab => {
+            CursorState::Grab | CursorState::Lock => {
                 let _: () = unsafe { msg_send![cls, hide] };
                 let _ = CGDisplay::associate_mouse_and_mouse_cursor_position(false);
                 Ok(())
-            }
+            },
+            CursorState::Confine => Err("Cursor cannot be confined on macOS.".to_string()),
         }
     }
 
@@ -639,7 +665,7 @@ impl Window2 {
     }
 
     #[inline]
-    pub fn set_fullscreen(&self, _monitor: Option<RootMonitorId>) {
+    pub fn set_fullscreen(&self, _fullscreen: Option<Fullscreen>) {
         unimplemented!()
     }
 
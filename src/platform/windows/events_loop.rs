@@ -14,6 +14,7 @@
 
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::ffi::OsString;
 use std::mem;
 use std::os::windows::ffi::OsStringExt;
@@ -25,6 +26,7 @@ use std::sync::Barrier;
 use std::sync::Mutex;
 use std::sync::Condvar;
 use std::thread;
+use std::time::Duration;
 
 use kernel32;
 use shell32;
@@ -40,6 +42,7 @@ use ControlFlow;
 use CursorState;
 use Event;
 use EventsLoopClosed;
+use EventsLoopCreationError;
 use KeyboardInput;
 use WindowAttributes;
 use WindowEvent;
@@ -86,7 +89,7 @@ pub struct EventsLoop {
 }
 
 impl EventsLoop {
-    pub fn new() -> EventsLoop {
+    pub fn new() -> Result<EventsLoop, EventsLoopCreationError> {
         // The main events transfer channel.
         let (tx, rx) = mpsc::channel();
         let win32_block_loop = Arc::new((Mutex::new(false), Condvar::new()));
@@ -146,11 +149,11 @@ impl EventsLoop {
         // Blocks this function until the background thread has an events loop. See other comments.
         barrier.wait();
 
-        EventsLoop {
+        Ok(EventsLoop {
             thread_id: unsafe { kernel32::GetThreadId(thread.as_raw_handle()) },
             receiver: rx,
             win32_block_loop
-        }
+        })
     }
 
     pub fn poll_events<F>(&mut self, mut callback: F)
@@ -176,6 +179,30 @@ impl EventsLoop {
         }
     }
 
+    pub fn wait_events_timeout<F>(&mut self, timeout: Duration, mut callback: F)
+        where F: FnMut(Event)
+    {
+        // Block until the first event, then dispatch the rest of the queue without blocking.
+        let event = match self.receiver.recv_timeout(timeout) {
+            Ok(e) => e,
+            Err(_) => return
+        };
+        let is_resize = match event {
+            Event::WindowEvent{ event: WindowEvent::Resized(..), .. } => true,
+            _ => false
+        };
+
+        callback(event);
+        if is_resize {
+            let (ref mutex, ref cvar) = *self.win32_block_loop;
+            let mut block_thread = mutex.lock().unwrap();
+            *block_thread = false;
+            cvar.notify_all();
+        }
+
+        self.poll_events(callback);
+    }
+
     pub fn run_forever<F>(&mut self, mut callback: F)
         where F: FnMut(Event) -> ControlFlow
     {
@@ -203,6 +230,14 @@ impl EventsLoop {
         }
     }
 
+    pub fn get_available_devices(&self) -> VecDeque<super::DeviceId> {
+        VecDeque::new()
+    }
+
+    pub fn get_device_info(&self, _: &super::DeviceId) -> Option<::DeviceInfo> {
+        None
+    }
+
     pub fn create_proxy(&self) -> EventsLoopProxy {
         EventsLoopProxy {
             thread_id: self.thread_id,
@@ -332,6 +367,15 @@ pub unsafe extern "system" fn callback(window: winapi::HWND, msg: winapi::UINT,
             1
         },
 
+        winapi::WM_PAINT => {
+            use events::WindowEvent::RedrawRequested;
+            send_event(Event::WindowEvent {
+                window_id: SuperWindowId(WindowId(window)),
+                event: RedrawRequested,
+            });
+            user32::DefWindowProcW(window, msg, wparam, lparam)
+        },
+
         winapi::WM_SIZE => {
             use events::WindowEvent::Resized;
             let w = winapi::LOWORD(lparam as winapi::DWORD) as u32;
@@ -707,12 +751,12 @@ pub unsafe extern "system" fn callback(window: winapi::HWND, msg: winapi::UINT,
                         if let Ok(window_state) = w_stash.lock() {
                             if window_state.mouse_in_window {
                                 match window_state.cursor_state {
-                                    CursorState::Normal => {
+                                    CursorState::Normal | CursorState::Confine => {
                                         user32::SetCursor(user32::LoadCursorW(
                                                 ptr::null_mut(),
                                                 window_state.cursor));
                                     },
-                                    CursorState::Grab | CursorState::Hide => {
+                                    CursorState::Grab | CursorState::Hide | CursorState::Lock => {
                                         user32::SetCursor(ptr::null_mut());
                                     }
                                 }
@@ -18,6 +18,7 @@ use platform::platform::WindowId;
 
 use CreationError;
 use CursorState;
+use Fullscreen;
 use MouseCursor;
 use WindowAttributes;
 use MonitorId as RootMonitorId;
@@ -66,6 +67,14 @@ impl Window {
         }
     }
 
+    #[inline]
+    pub fn request_redraw(&self) {
+        // Windows coalesces the invalidated regions into a single `WM_PAINT` message.
+        unsafe {
+            user32::InvalidateRect(self.window.0, ptr::null(), winapi::FALSE);
+        }
+    }
+
     #[inline]
     pub fn show(&self) {
         unsafe {
@@ -200,48 +209,43 @@ impl Window {
 
         unsafe { user32::AttachThreadInput(foreground_thread_id, current_thread_id, 1) };
 
-        let res = match (state, current_state.cursor_state) {
-            (CursorState::Normal, CursorState::Normal) => Ok(()),
-            (CursorState::Hide, CursorState::Hide) => Ok(()),
-            (CursorState::Grab, CursorState::Grab) => Ok(()),
-
-            (CursorState::Hide, CursorState::Normal) => {
-                current_state.cursor_state = CursorState::Hide;
-                Ok(())
-            },
+        let res = match state {
+            _ if state == current_state.cursor_state => Ok(()),
 
-            (CursorState::Normal, CursorState::Hide) => {
-                current_state.cursor_state = CursorState::Normal;
-                Ok(())
-            },
-
-            (CursorState::Grab, CursorState::Normal) | (CursorState::Grab, CursorState::Hide) => {
+            CursorState::Normal | CursorState::Hide => {
                 unsafe {
-                    let mut rect = mem::uninitialized();
-                    if user32::GetClientRect(self.window.0, &mut rect) == 0 {
-                        return Err(format!("GetWindowRect failed"));
-                    }
-                    user32::ClientToScreen(self.window.0, mem::transmute(&mut rect.left));
-                    user32::ClientToScreen(self.window.0, mem::transmute(&mut rect.right));
-                    if user32::ClipCursor(&rect) == 0 {
-                        return Err(format!("ClipCursor failed"));
+                    if user32::ClipCursor(ptr::null()) == 0 {
+                        Err(format!("ClipCursor failed"))
+                    } else {
+                        current_state.cursor_state = state;
+                        Ok(())
                     }
-                    current_state.cursor_state = CursorState::Grab;
-                    Ok(())
                 }
             },
 
-            (CursorState::Normal, CursorState::Grab) => {
+            CursorState::Grab | CursorState::Confine | CursorState::Lock => {
                 unsafe {
-                    if user32::ClipCursor(ptr::null()) == 0 {
-                        return Err(format!("ClipCursor failed"));
+                    let mut rect: winapi::RECT = mem::uninitialized();
+                    if user32::GetClientRect(self.window.0, &mut rect) == 0 {
+                        Err(format!("GetWindowRect failed"))
+                    } else {
+                        user32::ClientToScreen(self.window.0, mem::transmute(&mut rect.left));
+                        user32::ClientToScreen(self.window.0, mem::transmute(&mut rect.right));
+                        if state == CursorState::Lock {
+                            // clipping to a single pixel at the center keeps the cursor in place
+                            let x = (rect.left + rect.right) / 2;
+                            let y = (rect.top + rect.bottom) / 2;
+                            rect = winapi::RECT { left: x, top: y, right: x + 1, bottom: y + 1 };
+                        }
+                        if user32::ClipCursor(&rect) == 0 {
+                            Err(format!("ClipCursor failed"))
+                        } else {
+                            current_state.cursor_state = state;
+                            Ok(())
+                        }
                     }
-                    current_state.cursor_state = CursorState::Normal;
-                    Ok(())
                 }
             },
-
-            _ => unimplemented!(),
         };
 
         unsafe { user32::AttachThreadInput(foreground_thread_id, current_thread_id, 0) };
@@ -284,7 +288,7 @@ impl Window {
     }
 
     #[inline]
-    pub fn set_fullscreen(&self, _monitor: Option<RootMonitorId>) {
+    pub fn set_fullscreen(&self, _fullscreen: Option<Fullscreen>) {
         unimplemented!()
     }
 
@@ -335,7 +339,7 @@ unsafe fn init(window: WindowAttributes, pl_attribs: PlatformSpecificWindowBuild
     // switching to fullscreen if necessary
     // this means adjusting the window's position so that it overlaps the right monitor,
     //  and change the monitor's resolution if necessary
-    let fullscreen = if let Some(RootMonitorId { ref inner }) = window.fullscreen {
+    let fullscreen = if let Some(&RootMonitorId { ref inner }) = window.fullscreen.as_ref().map(Fullscreen::get_monitor) {
         try!(switch_to_fullscreen(&mut rect, inner));
         true
     } else {
@@ -0,0 +1,183 @@
+use std::cmp;
+use std::time::{Duration, Instant};
+
+use TimerId;
+
+struct Timer {
+    id: TimerId,
+    deadline: Instant,
+    // `Some` if the timer is repeating.
+    interval: Option<Duration>,
+}
+
+/// Timers registered on an `EventsLoop`.
+///
+/// They don't need any support from the platform: the events loop waits for events with a timeout
+/// that ends at the next deadline, then emits an `Event::Timer` for every timer that expired.
+///
+/// The current time is given by the caller, so that the timers can be tested without waiting.
+pub struct Timers {
+    next_id: u64,
+    timers: Vec<Timer>,
+}
+
+impl Timers {
+    pub fn new() -> Timers {
+        Timers {
+            next_id: 0,
+            timers: Vec::new(),
+        }
+    }
+
+    /// The interval of a repeating timer is at least one millisecond, so that it can't expire on
+    /// every pass of the events loop.
+    pub fn add(&mut self, now: Instant, duration: Duration, repeating: bool) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        let min_interval = Duration::from_millis(1);
+        self.timers.push(Timer {
+            id: id,
+            deadline: now + duration,
+            interval: if repeating { Some(cmp::max(duration, min_interval)) } else { None },
+        });
+        id
+    }
+
+    /// Returns `false` if the timer had already expired or been cancelled.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != len
+    }
+
+    /// Returns the earliest deadline of the active timers.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.deadline).min()
+    }
+
+    /// Returns how long to wait from `now` until the next deadline, capped to `timeout` if there
+    /// is one.
+    pub fn timeout(&self, now: Instant, timeout: Option<Duration>) -> Option<Duration> {
+        let until_deadline = self.next_deadline().map(|deadline| {
+            if deadline > now { deadline - now } else { Duration::new(0, 0) }
+        });
+        match (until_deadline, timeout) {
+            (Some(a), Some(b)) => Some(if a < b { a } else { b }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns the ids of the timers that expired at `now`, in the order of their deadlines.
+    /// Repeating timers are scheduled again, the others are removed.
+    pub fn expire(&mut self, now: Instant) -> Vec<TimerId> {
+        let mut expired: Vec<(Instant, TimerId)> = self.timers.iter()
+            .filter(|timer| timer.deadline <= now)
+            .map(|timer| (timer.deadline, timer.id))
+            .collect();
+        if expired.is_empty() {
+            return Vec::new();
+        }
+        expired.sort();
+
+        self.timers.retain(|timer| timer.deadline > now || timer.interval.is_some());
+        for timer in self.timers.iter_mut().filter(|timer| timer.deadline <= now) {
+            let interval = timer.interval.unwrap();
+            timer.deadline += interval;
+            // Don't try to catch up if we fell behind, a single event is emitted anyway.
+            if timer.deadline <= now {
+                timer.deadline = now + interval;
+            }
+        }
+
+        expired.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Timers;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn expired(timers: &mut Timers, now: Instant) -> Vec<u64> {
+        timers.expire(now).into_iter().map(|id| id.0).collect()
+    }
+
+    #[test]
+    fn fires_in_deadline_order() {
+        let start = Instant::now();
+        let mut timers = Timers::new();
+        let late = timers.add(start, ms(200), false);
+        let early = timers.add(start, ms(100), false);
+        assert_eq!(timers.timeout(start, None), Some(ms(100)));
+        assert_eq!(expired(&mut timers, start + ms(99)), vec![]);
+
+        assert_eq!(expired(&mut timers, start + ms(250)), vec![early.0, late.0]);
+        assert!(timers.next_deadline().is_none());
+    }
+
+    #[test]
+    fn caps_timeout() {
+        let start = Instant::now();
+        let mut timers = Timers::new();
+        assert_eq!(timers.timeout(start, None), None);
+        assert_eq!(timers.timeout(start, Some(ms(50))), Some(ms(50)));
+
+        timers.add(start, ms(100), false);
+        assert_eq!(timers.timeout(start, Some(ms(50))), Some(ms(50)));
+        assert_eq!(timers.timeout(start, Some(ms(150))), Some(ms(100)));
+        assert_eq!(timers.timeout(start + ms(150), None), Some(ms(0)));
+    }
+
+    #[test]
+    fn reschedules_repeating_timers() {
+        let start = Instant::now();
+        let mut timers = Timers::new();
+        let id = timers.add(start, ms(100), true);
+
+        assert_eq!(expired(&mut timers, start + ms(120)), vec![id.0]);
+        assert_eq!(timers.next_deadline(), Some(start + ms(200)));
+        assert_eq!(expired(&mut timers, start + ms(200)), vec![id.0]);
+        assert_eq!(timers.next_deadline(), Some(start + ms(300)));
+    }
+
+    #[test]
+    fn doesnt_catch_up_missed_expirations() {
+        let start = Instant::now();
+        let mut timers = Timers::new();
+        let id = timers.add(start, ms(5), true);
+
+        assert_eq!(expired(&mut timers, start + ms(30)), vec![id.0]);
+        assert_eq!(timers.next_deadline(), Some(start + ms(35)));
+        assert_eq!(expired(&mut timers, start + ms(30)), vec![]);
+    }
+
+    #[test]
+    fn clamps_zero_interval() {
+        let start = Instant::now();
+        let mut timers = Timers::new();
+        let id = timers.add(start, ms(0), true);
+
+        assert_eq!(expired(&mut timers, start), vec![id.0]);
+        assert_eq!(timers.next_deadline(), Some(start + ms(1)));
+        assert_eq!(expired(&mut timers, start), vec![]);
+    }
+
+    #[test]
+    fn cancels_timers() {
+        let start = Instant::now();
+        let mut timers = Timers::new();
+        let id = timers.add(start, ms(0), false);
+        assert!(timers.cancel(id));
+        assert!(!timers.cancel(id));
+        assert_eq!(expired(&mut timers, start), vec![]);
+
+        let id = timers.add(start, ms(0), false);
+        assert_eq!(expired(&mut timers, start), vec![id.0]);
+        assert!(!timers.cancel(id));
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::vec_deque::IntoIter as VecDequeIter;
+use std::fmt;
 
 use CreationError;
 use CursorState;
@@ -55,11 +56,11 @@ impl WindowBuilder {
         self
     }
 
-    /// Sets the window fullscreen state. None means a normal window, Some(MonitorId)
-    /// means a fullscreen window on that specific monitor
+    /// Sets the window fullscreen state. None means a normal window, see `Fullscreen` for the
+    /// fullscreen states.
     #[inline]
-    pub fn with_fullscreen(mut self, monitor: Option<MonitorId>) -> WindowBuilder {
-        self.window.fullscreen = monitor;
+    pub fn with_fullscreen(mut self, fullscreen: Option<Fullscreen>) -> WindowBuilder {
+        self.window.fullscreen = fullscreen;
         self
     }
 
@@ -102,11 +103,17 @@ impl WindowBuilder {
     ///
     /// Error should be very rare and only occur in case of permission denied, incompatible system,
     /// out of memory, etc.
-    pub fn build(mut self, events_loop: &EventsLoop) -> Result<Window, CreationError> {
+    pub fn build<T>(mut self, events_loop: &EventsLoop<T>) -> Result<Window, CreationError> {
         // resizing the window to the dimensions of the monitor when fullscreen
         if self.window.dimensions.is_none() {
-            if let Some(ref monitor) = self.window.fullscreen {
-                self.window.dimensions = Some(monitor.get_dimensions());
+            match self.window.fullscreen {
+                Some(Fullscreen::Borderless(ref monitor)) => {
+                    self.window.dimensions = Some(monitor.get_dimensions());
+                },
+                Some(Fullscreen::Exclusive(ref video_mode)) => {
+                    self.window.dimensions = Some(video_mode.dimensions);
+                },
+                None => (),
             }
         }
 
@@ -130,7 +137,7 @@ impl Window {
     /// Error should be very rare and only occur in case of permission denied, incompatible system,
     ///  out of memory, etc.
     #[inline]
-    pub fn new(events_loop: &EventsLoop) -> Result<Window, CreationError> {
+    pub fn new<T>(events_loop: &EventsLoop<T>) -> Result<Window, CreationError> {
         let builder = WindowBuilder::new();
         builder.build(events_loop)
     }
@@ -143,6 +150,15 @@ impl Window {
         self.window.set_title(title)
     }
 
+    /// Requests a `WindowEvent::RedrawRequested` event to be emitted.
+    ///
+    /// Several requests made before the event is delivered only result in a single event, which
+    /// is also merged with the redraws requested by the system.
+    #[inline]
+    pub fn request_redraw(&self) {
+        self.window.request_redraw()
+    }
+
     /// Shows the window if it was hidden.
     ///
     /// ## Platform-specific
@@ -309,8 +325,8 @@ impl Window {
 
     /// Sets the window to fullscreen or back
     #[inline]
-    pub fn set_fullscreen(&self, monitor: Option<MonitorId>) {
-        self.window.set_fullscreen(monitor)
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.window.set_fullscreen(fullscreen)
     }
 
     /// Returns the current monitor the window is on or the primary monitor is nothing
@@ -379,4 +395,157 @@ impl MonitorId {
     pub fn get_hidpi_factor(&self) -> f32 {
         self.inner.get_hidpi_factor()
     }
+
+    /// Returns the physical size of the monitor, in millimeters.
+    ///
+    /// Returns `None` if it is unknown, as with projectors.
+    #[inline]
+    pub fn get_physical_size_mm(&self) -> Option<(u32, u32)> {
+        self.inner.get_physical_size_mm()
+    }
+
+    /// Returns the refresh rate of the monitor, in Hz.
+    ///
+    /// Returns `None` if it is unknown.
+    #[inline]
+    pub fn get_refresh_rate(&self) -> Option<u16> {
+        self.inner.get_refresh_rate()
+    }
+
+    /// Returns the manufacturer of the monitor.
+    ///
+    /// On X11, this is the three-letter manufacturer ID of its EDID.
+    #[inline]
+    pub fn get_make(&self) -> Option<String> {
+        self.inner.get_make()
+    }
+
+    /// Returns the model of the monitor.
+    #[inline]
+    pub fn get_model(&self) -> Option<String> {
+        self.inner.get_model()
+    }
+
+    /// Returns the arrangement of the subpixels of the monitor.
+    #[inline]
+    pub fn get_subpixel_layout(&self) -> SubpixelLayout {
+        self.inner.get_subpixel_layout()
+    }
+
+    /// Returns the rotation of the picture shown by the monitor.
+    #[inline]
+    pub fn get_rotation(&self) -> MonitorRotation {
+        self.inner.get_rotation()
+    }
+
+    /// Returns the video modes the monitor supports.
+    ///
+    /// Only X11 and Wayland report them, the list is empty on the other platforms.
+    #[inline]
+    pub fn get_video_modes(&self) -> VideoModesIter {
+        VideoModesIter { data: self.inner.get_video_modes().into_iter() }
+    }
+
+    /// Returns the video mode the monitor currently uses.
+    ///
+    /// Returns `None` if it is unknown.
+    #[inline]
+    pub fn get_current_video_mode(&self) -> Option<VideoMode> {
+        self.inner.get_current_video_mode()
+    }
+}
+
+impl fmt::Debug for MonitorId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MonitorId")
+            .field("name", &self.get_name())
+            .field("dimensions", &self.get_dimensions())
+            .field("position", &self.get_position())
+            .field("hidpi_factor", &self.get_hidpi_factor())
+            .finish()
+    }
+}
+
+/// The arrangement of the subpixels of a monitor, from left to right or from top to bottom.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum SubpixelLayout {
+    /// The layout is unknown.
+    Unknown,
+    /// The pixels aren't divided into subpixels.
+    None,
+    HorizontalRgb,
+    HorizontalBgr,
+    VerticalRgb,
+    VerticalBgr,
+}
+
+/// The counter-clockwise rotation of the picture shown by a monitor.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum MonitorRotation {
+    Normal,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+/// An iterator for the list of video modes of a monitor.
+pub struct VideoModesIter {
+    pub(crate) data: VecDequeIter<VideoMode>,
+}
+
+impl Iterator for VideoModesIter {
+    type Item = VideoMode;
+
+    #[inline]
+    fn next(&mut self) -> Option<VideoMode> {
+        self.data.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.data.size_hint()
+    }
+}
+
+/// The fullscreen state of a window.
+#[derive(Clone)]
+pub enum Fullscreen {
+    /// The window covers the monitor, whose video mode is left unchanged.
+    Borderless(MonitorId),
+
+    /// The window covers the monitor of the video mode, which is switched to while the window
+    /// has the focus. The original video mode is restored when the window leaves fullscreen,
+    /// loses the focus or is destroyed.
+    ///
+    /// The video mode is only switched on X11 and, when the window is created, on Windows. The
+    /// other platforms behave as with `Borderless`.
+    Exclusive(VideoMode),
+}
+
+impl Fullscreen {
+    /// Returns the monitor the window is fullscreen on.
+    #[inline]
+    pub fn get_monitor(&self) -> &MonitorId {
+        match *self {
+            Fullscreen::Borderless(ref monitor) => monitor,
+            Fullscreen::Exclusive(ref video_mode) => &video_mode.monitor,
+        }
+    }
+}
+
+/// A video mode of a monitor.
+#[derive(Clone)]
+pub struct VideoMode {
+    /// The monitor the video mode is for.
+    pub monitor: MonitorId,
+
+    /// The resolution of the video mode, in pixels.
+    pub dimensions: (u32, u32),
+
+    /// The refresh rate of the video mode, in Hz. When switching to the video mode, the available
+    /// refresh rate that is the closest is used. It is 0 when unknown.
+    pub refresh_rate: u16,
+
+    /// The number of bits per pixel. It isn't taken into account when switching to the video mode.
+    pub bit_depth: u16,
 }